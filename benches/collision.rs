@@ -0,0 +1,84 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use river_raid::collision;
+use river_raid::{Components, EnemyKind, Entity, Game2DMatrix, GameSettings, Location, Tag};
+
+const SCREEN: (u16, u16) = (200, 120);
+
+// a game crowded with enemies and bullets at random positions of the screen.
+fn crowded_game(enemies: usize, bullets: usize) -> Game2DMatrix {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut game = Game2DMatrix::with_screen(GameSettings::default(), SCREEN.0, SCREEN.1);
+
+    let random_location = |rng: &mut StdRng| {
+        Location::new(
+            rng.gen_range(0.0..SCREEN.1 as f32),
+            rng.gen_range(0.0..SCREEN.0 as f32),
+        )
+    };
+
+    for _ in 0..enemies {
+        let kind = EnemyKind::ALL[rng.gen_range(0..EnemyKind::ALL.len())];
+        let location = random_location(&mut rng);
+        game.world.spawn(Components::enemy(kind, location, 1.0));
+    }
+    for _ in 0..bullets {
+        let location = random_location(&mut rng);
+        game.world.spawn(Components::player_bullet(location, 0));
+    }
+
+    game
+}
+
+// every bullet against every enemy, how the collisions were checked before the row index.
+fn nested_loops(game: &mut Game2DMatrix) -> Vec<Entity> {
+    let enemies = game.world.tagged(Tag::Enemy);
+    let mut hits = vec![];
+    for bullet in game.world.tagged(Tag::PlayerBullet) {
+        let bullet_box = game.entity_box(bullet).unwrap();
+        let velocity = game.entity_motion(bullet);
+        let target = enemies.iter().copied().find(|&enemy| {
+            game.world.is_alive(enemy)
+                && collision::hit(
+                    bullet_box,
+                    velocity,
+                    game.entity_box(enemy).unwrap(),
+                    game.entity_motion(enemy),
+                )
+        });
+        if let Some(enemy) = target {
+            game.world.despawn(bullet);
+            game.world.despawn(enemy);
+            hits.push(enemy);
+        }
+    }
+    hits
+}
+
+fn bullet_hits(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bullet_hits");
+
+    for &count in &[500, 2000, 8000] {
+        let game = crowded_game(count, count);
+
+        group.bench_with_input(BenchmarkId::new("row_index", count), &game, |b, game| {
+            b.iter_batched(
+                || game.clone(),
+                |mut game| game.resolve_bullet_hits(),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("nested_loops", count), &game, |b, game| {
+            b.iter_batched(
+                || game.clone(),
+                |mut game| nested_loops(&mut game),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bullet_hits);
+criterion_main!(benches);
@@ -0,0 +1,338 @@
+use crate::events::GameEvent;
+use crate::theme::{Paint, Theme};
+use crossterm::{
+    cursor::MoveTo,
+    style::{Print, ResetColor},
+    terminal::{Clear, ClearType},
+    QueueableCommand,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{self, Read, Stdout, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+// where the progress is kept between the sessions.
+pub const ACHIEVEMENTS_DB: &str = "achievements_db";
+const PROGRESS_KEY: &str = "progress";
+// an unreachable sync server gives up after this long instead of holding the exit of the game.
+const SYNC_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// @notice what an achievement counts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    EnemiesKilled,
+    BridgesDestroyed,
+    RowsTravelled,
+    PowerUpsCollected,
+    // the longest time alive without touching a fuel depot.
+    SecondsWithoutRefuel,
+}
+
+/// @notice whether the goal has to be reached within one run or across all of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scope {
+    Run,
+    Lifetime,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Achievement {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub metric: Metric,
+    pub scope: Scope,
+    pub goal: u64,
+}
+
+/// @notice every achievement of the game, a new one only needs an entry here.
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        id: "first_blood",
+        name: "First blood",
+        description: "destroy an enemy",
+        metric: Metric::EnemiesKilled,
+        scope: Scope::Lifetime,
+        goal: 1,
+    },
+    Achievement {
+        id: "bridge_buster",
+        name: "Bridge buster",
+        description: "destroy 10 bridges",
+        metric: Metric::BridgesDestroyed,
+        scope: Scope::Lifetime,
+        goal: 10,
+    },
+    Achievement {
+        id: "running_on_fumes",
+        name: "Running on fumes",
+        description: "survive 5 minutes without refuelling",
+        metric: Metric::SecondsWithoutRefuel,
+        scope: Scope::Run,
+        goal: 300,
+    },
+    Achievement {
+        id: "centurion",
+        name: "Centurion",
+        description: "100 kills in one run",
+        metric: Metric::EnemiesKilled,
+        scope: Scope::Run,
+        goal: 100,
+    },
+    Achievement {
+        id: "long_haul",
+        name: "Long haul",
+        description: "travel 100000 rows of river",
+        metric: Metric::RowsTravelled,
+        scope: Scope::Lifetime,
+        goal: 100_000,
+    },
+    Achievement {
+        id: "collector",
+        name: "Collector",
+        description: "collect 25 power-ups",
+        metric: Metric::PowerUpsCollected,
+        scope: Scope::Lifetime,
+        goal: 25,
+    },
+];
+
+/// @notice the counters the metrics are read from, for a run or for all the runs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Counters {
+    pub enemies_killed: u64,
+    pub bridges_destroyed: u64,
+    pub rows_travelled: u64,
+    pub power_ups_collected: u64,
+    pub seconds_without_refuel: u64,
+}
+
+impl Counters {
+    pub fn get(&self, metric: Metric) -> u64 {
+        match metric {
+            Metric::EnemiesKilled => self.enemies_killed,
+            Metric::BridgesDestroyed => self.bridges_destroyed,
+            Metric::RowsTravelled => self.rows_travelled,
+            Metric::PowerUpsCollected => self.power_ups_collected,
+            Metric::SecondsWithoutRefuel => self.seconds_without_refuel,
+        }
+    }
+
+    /// @notice the counters after adding a run, the records keep the best value instead of a sum.
+    pub fn merged(&self, run: &Counters) -> Counters {
+        Counters {
+            enemies_killed: self.enemies_killed + run.enemies_killed,
+            bridges_destroyed: self.bridges_destroyed + run.bridges_destroyed,
+            rows_travelled: self.rows_travelled + run.rows_travelled,
+            power_ups_collected: self.power_ups_collected + run.power_ups_collected,
+            seconds_without_refuel: self.seconds_without_refuel.max(run.seconds_without_refuel),
+        }
+    }
+}
+
+/// @notice what is stored between the sessions, the lifetime counters and when each achievement
+/// was unlocked as a unix timestamp.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Progress {
+    pub lifetime: Counters,
+    pub unlocked: BTreeMap<String, i64>,
+}
+
+/// @notice the local sled storage of the progress.
+pub struct AchievementStore {
+    db: sled::Db,
+}
+
+impl AchievementStore {
+    pub fn open(path: &str) -> sled::Result<Self> {
+        Ok(AchievementStore {
+            db: sled::open(path)?,
+        })
+    }
+
+    pub fn load(&self) -> sled::Result<Progress> {
+        Ok(self
+            .db
+            .get(PROGRESS_KEY)?
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default())
+    }
+
+    pub fn save(&self, progress: &Progress) -> sled::Result<()> {
+        let bytes = serde_json::to_vec(progress).expect("the progress serializes");
+        self.db.insert(PROGRESS_KEY, bytes)?;
+        self.db.flush()?;
+        Ok(())
+    }
+}
+
+/// @notice follows the events of a run and unlocks the achievements.
+#[derive(Clone, Debug, Default)]
+pub struct Achievements {
+    // the progress of the previous runs.
+    pub progress: Progress,
+    pub run: Counters,
+    // time alive since the last refuel.
+    refuel_free_ms: u64,
+}
+
+impl Achievements {
+    pub fn new(progress: Progress) -> Self {
+        Achievements {
+            progress,
+            ..Default::default()
+        }
+    }
+
+    /// @notice the value of the metric of an achievement, for its scope.
+    pub fn value(&self, achievement: &Achievement) -> u64 {
+        match achievement.scope {
+            Scope::Run => self.run.get(achievement.metric),
+            Scope::Lifetime => self
+                .progress
+                .lifetime
+                .merged(&self.run)
+                .get(achievement.metric),
+        }
+    }
+
+    pub fn is_unlocked(&self, achievement: &Achievement) -> bool {
+        self.progress.unlocked.contains_key(achievement.id)
+    }
+
+    /// @notice counts the events of a tick, returns the achievements they unlocked.
+    pub fn observe(&mut self, events: &[GameEvent]) -> Vec<&'static Achievement> {
+        for event in events {
+            match *event {
+                GameEvent::Tick { ms } => {
+                    self.refuel_free_ms += ms;
+                    self.run.seconds_without_refuel = self
+                        .run
+                        .seconds_without_refuel
+                        .max(self.refuel_free_ms / 1000);
+                }
+                GameEvent::RowTravelled => self.run.rows_travelled += 1,
+                GameEvent::EnemyKilled(_) => self.run.enemies_killed += 1,
+                GameEvent::BridgeDestroyed => self.run.bridges_destroyed += 1,
+                GameEvent::PowerUpCollected(_) => self.run.power_ups_collected += 1,
+                GameEvent::Refuelled { .. } | GameEvent::LifeLost(_) => self.refuel_free_ms = 0,
+                GameEvent::ShotFired { .. }
+                | GameEvent::BulletHit
+                | GameEvent::DepotShot
+                | GameEvent::BankClearance(_) => {}
+            }
+        }
+
+        let unlocked: Vec<&'static Achievement> = ACHIEVEMENTS
+            .iter()
+            .filter(|achievement| {
+                !self.is_unlocked(achievement) && self.value(achievement) >= achievement.goal
+            })
+            .collect();
+
+        let now = chrono::Utc::now().timestamp();
+        for achievement in unlocked.iter() {
+            self.progress
+                .unlocked
+                .insert(achievement.id.to_string(), now);
+        }
+        unlocked
+    }
+
+    /// @notice the progress to store once the run is over.
+    pub fn finish_run(&mut self) -> Progress {
+        self.progress.lifetime = self.progress.lifetime.merged(&self.run);
+        self.run = Counters::default();
+        self.refuel_free_ms = 0;
+        self.progress.clone()
+    }
+}
+
+/// @notice uploads the progress to the web server, under the account of the token.
+/// @dev a bare HTTP/1.1 request, the server answers on POST /achievements.
+pub fn sync(server: &str, token: &str, progress: &Progress) -> io::Result<()> {
+    let body = serde_json::to_string(progress)?;
+
+    let address = server.to_socket_addrs()?.next().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("achievements sync failed: unknown server {server}"),
+        )
+    })?;
+    let mut stream = TcpStream::connect_timeout(&address, SYNC_TIMEOUT)?;
+    stream.set_read_timeout(Some(SYNC_TIMEOUT))?;
+    stream.set_write_timeout(Some(SYNC_TIMEOUT))?;
+    write!(
+        stream,
+        "POST /achievements HTTP/1.1\r\nHost: {server}\r\nAuthorization: Bearer {token}\r\n\
+         Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    let status = response.lines().next().unwrap_or_default();
+    if status.split_whitespace().nth(1) == Some("200") {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "achievements sync failed: {status}"
+        )))
+    }
+}
+
+/// @notice the achievements screen, every achievement with its progress.
+pub fn draw_achievements(
+    screen: &mut Stdout,
+    achievements: &Achievements,
+    theme: &Theme,
+) -> io::Result<()> {
+    const BAR: u64 = 20;
+
+    screen
+        .queue(Clear(ClearType::All))?
+        .queue(MoveTo(2, 1))?
+        .queue(Print("ACHIEVEMENTS"))?;
+
+    for (k, achievement) in ACHIEVEMENTS.iter().enumerate() {
+        let value = achievement.goal.min(achievements.value(achievement));
+        let filled = (value * BAR / achievement.goal) as usize;
+        let unlocked = achievements.is_unlocked(achievement);
+        let row = 3 + 2 * k as u16;
+
+        screen
+            .queue(MoveTo(2, row))?
+            .queue(theme.paint(if unlocked {
+                Paint::Unlocked
+            } else {
+                Paint::Locked
+            }))?
+            .queue(Print(format!(
+                "{} {:<18} {}",
+                if unlocked {
+                    theme.glyph("🏆", "* ")
+                } else {
+                    "  "
+                },
+                achievement.name,
+                achievement.description
+            )))?
+            .queue(MoveTo(5, row + 1))?
+            .queue(ResetColor)?
+            .queue(theme.paint(Paint::Hud))?
+            .queue(Print(format!(
+                "[{}{}] {}/{}",
+                "#".repeat(filled),
+                "-".repeat(BAR as usize - filled),
+                value,
+                achievement.goal
+            )))?
+            .queue(ResetColor)?;
+    }
+
+    screen
+        .queue(MoveTo(2, 4 + 2 * ACHIEVEMENTS.len() as u16))?
+        .queue(Print("press any key"))?;
+    screen.flush()
+}
@@ -0,0 +1,63 @@
+/// @notice the animations played on the river.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimationKind {
+    // something shot down.
+    Explosion,
+    // the boat crashed, the life is lost once it is over.
+    Wreck,
+}
+
+impl AnimationKind {
+    /// @notice the emoji and the ascii frames, the ascii ones as wide as the emoji.
+    pub fn frames(self) -> (&'static [&'static str], &'static [&'static str]) {
+        match self {
+            AnimationKind::Explosion => (&["💥", "💥", "🔥", "💨"], &["@@", "**", "++", ".."]),
+            AnimationKind::Wreck => (
+                &["💥", "💥", "🔥", "🔥", "💨", "💨"],
+                &["XX", "##", "##", "**", "~~", ".."],
+            ),
+        }
+    }
+
+    pub fn ticks_per_frame(self) -> u16 {
+        match self {
+            AnimationKind::Explosion => 2,
+            AnimationKind::Wreck => 5,
+        }
+    }
+
+    /// @notice ticks the whole animation lasts.
+    pub fn length(self) -> u16 {
+        self.frames().0.len() as u16 * self.ticks_per_frame()
+    }
+}
+
+/// @notice an animation playing on an entity, driven by the ticks of the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Animation {
+    pub kind: AnimationKind,
+    // ticks since the animation started.
+    pub age: u16,
+}
+
+impl Animation {
+    pub fn new(kind: AnimationKind) -> Self {
+        Animation { kind, age: 0 }
+    }
+
+    /// @notice the emoji and the ascii glyph of the current frame.
+    pub fn frame(&self) -> (&'static str, &'static str) {
+        let (emoji, ascii) = self.kind.frames();
+        let k = (self.age / self.kind.ticks_per_frame()) as usize;
+        (emoji[k.min(emoji.len() - 1)], ascii[k.min(ascii.len() - 1)])
+    }
+
+    /// @notice how far the animation is, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        self.age as f32 / self.kind.length() as f32
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.age >= self.kind.length()
+    }
+}
@@ -0,0 +1,247 @@
+use crate::enemy::Hitbox;
+use crate::{Location, Velocity};
+
+// emoji like ⛵ and 🔥 take two terminal cells.
+pub const WIDE_GLYPH: f32 = 2.0;
+
+/// @notice an axis aligned box in world cells, covering [top, top + height) x [left, left + width).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub top: f32,
+    pub left: f32,
+    pub height: f32,
+    pub width: f32,
+}
+
+impl Aabb {
+    pub fn new(top: f32, left: f32, height: f32, width: f32) -> Self {
+        Aabb {
+            top,
+            left,
+            height,
+            width,
+        }
+    }
+
+    pub fn at(location: &Location, height: f32, width: f32) -> Self {
+        Aabb::new(location.element_i, location.element_j, height, width)
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.top + self.height
+    }
+
+    pub fn right(&self) -> f32 {
+        self.left + self.width
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.top < other.bottom()
+            && other.top < self.bottom()
+            && self.left < other.right()
+            && other.left < self.right()
+    }
+
+    /// @notice the box covering everything this box went through during a tick of the given motion.
+    pub fn swept(&self, motion: Velocity) -> Aabb {
+        let (top, left) = (
+            self.top.min(self.top - motion.di),
+            self.left.min(self.left - motion.dj),
+        );
+        Aabb::new(
+            top,
+            left,
+            self.height + motion.di.abs(),
+            self.width + motion.dj.abs(),
+        )
+    }
+}
+
+/// @notice whether two moving boxes touched during the last tick.
+/// @dev the first box is swept by the motion relative to the second one, so fast objects like the
+/// bullets can not tunnel through what they hit.
+pub fn hit(a: Aabb, a_motion: Velocity, b: Aabb, b_motion: Velocity) -> bool {
+    let relative = Velocity::new(a_motion.di - b_motion.di, a_motion.dj - b_motion.dj);
+    a.swept(relative).intersects(&b)
+}
+
+/// @notice the cells an entity occupies from its position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Body {
+    Cells(Hitbox),
+    // the whole row of the river, from bank to bank.
+    Span,
+}
+
+impl Body {
+    /// @notice the box of the body at the given position, banks are the ones of the position row.
+    pub fn aabb(self, position: &Location, banks: (u16, u16)) -> Aabb {
+        match self {
+            Body::Cells(hitbox) => Aabb::at(position, hitbox.height as f32, hitbox.width as f32),
+            Body::Span => Aabb::new(
+                position.element_i,
+                banks.0 as f32,
+                1.0,
+                banks.1.saturating_sub(banks.0) as f32,
+            ),
+        }
+    }
+}
+
+/// @notice the boat at column player_i and row player_j.
+pub fn boat_box(player_i: u16, player_j: u16) -> Aabb {
+    Aabb::new(player_j as f32, player_i as f32, 1.0, WIDE_GLYPH)
+}
+
+/// @notice whether the boat touches a bank, i.e. is not fully inside the water (banks.0, banks.1).
+pub fn hits_bank(boat: &Aabb, banks: (u16, u16)) -> bool {
+    boat.left <= banks.0 as f32 || boat.right() > banks.1 as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::Components;
+    use crate::EnemyKind;
+
+    const STILL: Velocity = Velocity { di: 0.0, dj: 0.0 };
+    const BANKS: (u16, u16) = (10, 40);
+
+    fn aabb(entity: &Components) -> Aabb {
+        entity.body.aabb(&entity.position, BANKS)
+    }
+
+    fn bullet(i: f32, j: f32) -> Components {
+        Components::player_bullet(Location::new(i, j), 0)
+    }
+
+    fn enemy(kind: EnemyKind, i: f32, j: f32) -> Components {
+        Components::enemy(kind, Location::new(i, j), 1.0)
+    }
+
+    fn enemy_bullet(i: f32, j: f32, velocity: Velocity) -> Components {
+        Components::enemy_bullet(Location::new(i, j), velocity)
+    }
+
+    fn fuel(i: f32, j: f32) -> Components {
+        Components::fuel_depot(Location::new(i, j))
+    }
+
+    fn bridge(i: f32) -> Components {
+        Components::bridge(Location::new(i, BANKS.0 as f32))
+    }
+
+    #[test]
+    fn boat_against_banks() {
+        assert!(!hits_bank(&boat_box(20, 30), (10, 40)));
+        assert!(hits_bank(&boat_box(10, 30), (10, 40)));
+        // the boat is two cells wide, its right half is on the bank.
+        assert!(hits_bank(&boat_box(39, 30), (10, 40)));
+        assert!(hits_bank(&boat_box(0, 30), (0, 40)));
+    }
+
+    #[test]
+    fn boat_against_enemy() {
+        let boat = boat_box(20, 30);
+        let ship = enemy(EnemyKind::Ship, 30.0, 19.0);
+        assert!(hit(aabb(&ship), STILL, boat, STILL));
+
+        let far = enemy(EnemyKind::Ship, 30.0, 25.0);
+        assert!(!hit(aabb(&far), STILL, boat, STILL));
+
+        // the ship takes the two cells of its glyph, the water right of it is free.
+        let beside = enemy(EnemyKind::Ship, 30.0, 18.0);
+        assert!(!hit(aabb(&beside), STILL, boat, STILL));
+
+        // the right cell of a wide glyph counts as well.
+        let mine = enemy(EnemyKind::Mine, 30.0, 21.5);
+        assert!(hit(aabb(&mine), STILL, boat, STILL));
+    }
+
+    #[test]
+    fn boat_against_enemy_scrolled_over_it() {
+        // the river scrolled two rows, the enemy went from row 29 to row 31.
+        let boat = boat_box(20, 30);
+        let heli = enemy(EnemyKind::Helicopter, 31.0, 20.0);
+        assert!(!hit(aabb(&heli), STILL, boat, STILL));
+        assert!(hit(aabb(&heli), Velocity::new(2.0, 0.0), boat, STILL));
+    }
+
+    #[test]
+    fn boat_against_enemy_bullet() {
+        let boat = boat_box(20, 30);
+        let shot = enemy_bullet(30.0, 21.0, Velocity::new(1.5, 0.0));
+        assert!(hit(aabb(&shot), shot.velocity, boat, STILL));
+
+        // a fast shot that jumped over the boat row during the tick.
+        let tunnelling = enemy_bullet(31.2, 20.0, Velocity::new(3.0, 0.0));
+        assert!(!boat.intersects(&aabb(&tunnelling)));
+        assert!(hit(aabb(&tunnelling), tunnelling.velocity, boat, STILL));
+
+        let beside = enemy_bullet(30.0, 25.0, Velocity::new(1.5, 0.0));
+        assert!(!hit(aabb(&beside), beside.velocity, boat, STILL));
+    }
+
+    #[test]
+    fn boat_against_bridge() {
+        let boat = boat_box(20, 30);
+        assert!(hit(aabb(&bridge(30.0)), STILL, boat, STILL));
+
+        let ahead = bridge(27.0);
+        assert!(!hit(aabb(&ahead), Velocity::new(1.0, 0.0), boat, STILL));
+    }
+
+    #[test]
+    fn boat_against_fuel_depot() {
+        let boat = boat_box(20, 30);
+        // the depot is four rows tall.
+        assert!(boat.intersects(&aabb(&fuel(27.0, 21.0))));
+        assert!(!boat.intersects(&aabb(&fuel(25.0, 21.0))));
+        assert!(!boat.intersects(&aabb(&fuel(27.0, 23.0))));
+    }
+
+    #[test]
+    fn bullet_against_enemy() {
+        let jet = enemy(EnemyKind::Jet, 10.0, 20.0);
+        let shot = bullet(10.0, 21.0);
+        assert!(hit(aabb(&shot), shot.velocity, aabb(&jet), STILL));
+
+        // moving two rows per tick, the bullet went from row 11 to row 9 past the enemy on row 10.
+        let past = bullet(9.0, 21.0);
+        assert!(!aabb(&past).intersects(&aabb(&jet)));
+        assert!(hit(aabb(&past), past.velocity, aabb(&jet), STILL));
+
+        let aside = bullet(10.0, 24.0);
+        assert!(!hit(aabb(&aside), aside.velocity, aabb(&jet), STILL));
+
+        // no underflow for objects at the first column.
+        let edge = enemy(EnemyKind::Mine, 10.0, 0.0);
+        assert!(hit(aabb(&bullet(10.0, 0.0)), STILL, aabb(&edge), STILL));
+    }
+
+    #[test]
+    fn bullet_against_bridge() {
+        let bridge = aabb(&bridge(10.0));
+
+        let past = bullet(9.0, 35.0);
+        assert!(hit(
+            aabb(&past),
+            past.velocity,
+            bridge,
+            Velocity::new(1.0, 0.0)
+        ));
+
+        let over_bank = bullet(10.0, 45.0);
+        assert!(!hit(aabb(&over_bank), over_bank.velocity, bridge, STILL));
+    }
+
+    #[test]
+    fn bullet_against_fuel_depot() {
+        let depot = fuel(10.0, 20.0);
+        let shot = bullet(12.0, 19.0);
+        assert!(hit(aabb(&shot), shot.velocity, aabb(&depot), STILL));
+
+        let aside = bullet(12.0, 22.0);
+        assert!(!hit(aabb(&aside), aside.velocity, aabb(&depot), STILL));
+    }
+}
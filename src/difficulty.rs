@@ -0,0 +1,259 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// @notice the difficulty presets that can be picked at startup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DifficultyPreset {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl FromStr for DifficultyPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "easy" => Ok(DifficultyPreset::Easy),
+            "normal" => Ok(DifficultyPreset::Normal),
+            "hard" => Ok(DifficultyPreset::Hard),
+            other => Err(format!(
+                "unknown difficulty `{other}`, expected easy, normal or hard"
+            )),
+        }
+    }
+}
+
+/// @notice the game parameters once the boat has travelled `distance` rows.
+/// @dev the parameters between two stages are interpolated, see Difficulty::at.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stage {
+    pub distance: u32,
+    // duration of a tick, the throttle sets how many rows the river scrolls in a tick.
+    pub tick_ms: u64,
+    pub enemy_chance: f64,
+    pub fuel_chance: f64,
+    // spawn weights in the order of EnemyKind::ALL.
+    pub enemy_mix: [u32; 4],
+    // the boat burns one unit of fuel every fuel_drain_every ticks.
+    pub fuel_drain_every: u32,
+    // (min, max) width of the river.
+    pub river_width: (u16, u16),
+    pub enemy_fire_rate: f64,
+    pub power_up_chance: f64,
+}
+
+const EASY: [Stage; 3] = [
+    Stage {
+        distance: 0,
+        tick_ms: 70,
+        enemy_chance: 0.05,
+        fuel_chance: 0.03,
+        enemy_mix: [6, 2, 0, 2],
+        fuel_drain_every: 3,
+        river_width: (40, 80),
+        enemy_fire_rate: 0.0,
+        power_up_chance: 0.006,
+    },
+    Stage {
+        distance: 3000,
+        tick_ms: 60,
+        enemy_chance: 0.08,
+        fuel_chance: 0.025,
+        enemy_mix: [5, 3, 1, 2],
+        fuel_drain_every: 3,
+        river_width: (30, 70),
+        enemy_fire_rate: 0.003,
+        power_up_chance: 0.005,
+    },
+    Stage {
+        distance: 10000,
+        tick_ms: 50,
+        enemy_chance: 0.1,
+        fuel_chance: 0.02,
+        enemy_mix: [4, 3, 1, 2],
+        fuel_drain_every: 2,
+        river_width: (24, 60),
+        enemy_fire_rate: 0.006,
+        power_up_chance: 0.004,
+    },
+];
+
+const NORMAL: [Stage; 3] = [
+    Stage {
+        distance: 0,
+        tick_ms: 60,
+        enemy_chance: 0.08,
+        fuel_chance: 0.02,
+        enemy_mix: [5, 3, 1, 2],
+        fuel_drain_every: 2,
+        river_width: (30, 70),
+        enemy_fire_rate: 0.005,
+        power_up_chance: 0.004,
+    },
+    Stage {
+        distance: 2000,
+        tick_ms: 50,
+        enemy_chance: 0.12,
+        fuel_chance: 0.015,
+        enemy_mix: [4, 3, 2, 3],
+        fuel_drain_every: 2,
+        river_width: (20, 50),
+        enemy_fire_rate: 0.01,
+        power_up_chance: 0.0035,
+    },
+    Stage {
+        distance: 8000,
+        tick_ms: 40,
+        enemy_chance: 0.16,
+        fuel_chance: 0.012,
+        enemy_mix: [3, 3, 3, 3],
+        fuel_drain_every: 1,
+        river_width: (14, 40),
+        enemy_fire_rate: 0.02,
+        power_up_chance: 0.003,
+    },
+];
+
+const HARD: [Stage; 3] = [
+    Stage {
+        distance: 0,
+        tick_ms: 50,
+        enemy_chance: 0.12,
+        fuel_chance: 0.015,
+        enemy_mix: [4, 3, 2, 3],
+        fuel_drain_every: 2,
+        river_width: (20, 50),
+        enemy_fire_rate: 0.01,
+        power_up_chance: 0.003,
+    },
+    Stage {
+        distance: 1500,
+        tick_ms: 40,
+        enemy_chance: 0.18,
+        fuel_chance: 0.012,
+        enemy_mix: [3, 3, 3, 3],
+        fuel_drain_every: 1,
+        river_width: (14, 40),
+        enemy_fire_rate: 0.02,
+        power_up_chance: 0.0025,
+    },
+    Stage {
+        distance: 6000,
+        tick_ms: 30,
+        enemy_chance: 0.25,
+        fuel_chance: 0.01,
+        enemy_mix: [2, 4, 4, 3],
+        fuel_drain_every: 1,
+        river_width: (10, 30),
+        enemy_fire_rate: 0.035,
+        power_up_chance: 0.002,
+    },
+];
+
+/// @notice the difficulty curve of a game, a list of stages ordered by distance.
+#[derive(Clone, Debug)]
+pub struct Difficulty {
+    pub preset: DifficultyPreset,
+    pub stages: Vec<Stage>,
+}
+
+impl Default for Stage {
+    fn default() -> Self {
+        NORMAL[0]
+    }
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Difficulty::from_preset(DifficultyPreset::default())
+    }
+}
+
+fn lerp(from: f64, to: f64, t: f64) -> f64 {
+    from + (to - from) * t
+}
+
+impl Difficulty {
+    pub fn from_preset(preset: DifficultyPreset) -> Self {
+        let stages = match preset {
+            DifficultyPreset::Easy => EASY.to_vec(),
+            DifficultyPreset::Normal => NORMAL.to_vec(),
+            DifficultyPreset::Hard => HARD.to_vec(),
+        };
+        Difficulty { preset, stages }
+    }
+
+    /// @notice the parameters of the game after travelling the given distance.
+    /// @dev interpolates linearly between the surrounding stages, the last stage holds forever.
+    pub fn at(&self, distance: u32) -> Stage {
+        let next = self
+            .stages
+            .iter()
+            .position(|stage| stage.distance > distance);
+        let (from, to) = match next {
+            Some(0) => return self.stages[0],
+            Some(idx) => (self.stages[idx - 1], self.stages[idx]),
+            None => {
+                return *self
+                    .stages
+                    .last()
+                    .expect("a difficulty has at least one stage")
+            }
+        };
+
+        let t = (distance - from.distance) as f64 / (to.distance - from.distance) as f64;
+        let lerp_u = |a: u32, b: u32| lerp(a as f64, b as f64, t).round() as u32;
+
+        Stage {
+            distance,
+            tick_ms: lerp(from.tick_ms as f64, to.tick_ms as f64, t).round() as u64,
+            enemy_chance: lerp(from.enemy_chance, to.enemy_chance, t),
+            fuel_chance: lerp(from.fuel_chance, to.fuel_chance, t),
+            enemy_mix: std::array::from_fn(|k| lerp_u(from.enemy_mix[k], to.enemy_mix[k])),
+            fuel_drain_every: lerp_u(from.fuel_drain_every, to.fuel_drain_every).max(1),
+            river_width: (
+                lerp_u(from.river_width.0 as u32, to.river_width.0 as u32) as u16,
+                lerp_u(from.river_width.1 as u32, to.river_width.1 as u32) as u16,
+            ),
+            enemy_fire_rate: lerp(from.enemy_fire_rate, to.enemy_fire_rate, t),
+            power_up_chance: lerp(from.power_up_chance, to.power_up_chance, t),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_hold_at_their_distance() {
+        let difficulty = Difficulty::from_preset(DifficultyPreset::Normal);
+        assert_eq!(difficulty.at(0), NORMAL[0]);
+        assert_eq!(difficulty.at(NORMAL[1].distance), NORMAL[1]);
+        assert_eq!(difficulty.at(NORMAL[2].distance), NORMAL[2]);
+    }
+
+    #[test]
+    fn the_last_stage_holds_forever() {
+        let difficulty = Difficulty::from_preset(DifficultyPreset::Hard);
+        let last = difficulty.at(1_000_000);
+        assert_eq!(last, HARD[2]);
+    }
+
+    #[test]
+    fn stages_are_interpolated_between_them() {
+        let difficulty = Difficulty::from_preset(DifficultyPreset::Normal);
+        let halfway = difficulty.at(1000);
+        assert_eq!(halfway.distance, 1000);
+        assert_eq!(halfway.tick_ms, 55);
+        assert!((halfway.enemy_chance - 0.1).abs() < 1e-9);
+        assert_eq!(halfway.river_width, (25, 60));
+
+        // just before the next stage the parameters are almost those of the next stage.
+        let before = difficulty.at(NORMAL[1].distance - 1);
+        assert_eq!(before.tick_ms, NORMAL[1].tick_ms);
+        assert!(before.enemy_chance < NORMAL[1].enemy_chance);
+    }
+}
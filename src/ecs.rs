@@ -0,0 +1,462 @@
+use crate::animation::{Animation, AnimationKind};
+use crate::collision::Body;
+use crate::enemy::Hitbox;
+use crate::powerup::PowerUpKind;
+use crate::theme::Paint;
+use crate::{Enemy, EnemyKind, Location, Velocity, BRIDGE_BONUS, BULLET_SPEED, FUEL_DEPOT_POINTS};
+
+/// @notice a handle to an entity of the world.
+/// @dev the slots of despawned entities are reused, the generation tells a stale handle apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+}
+
+/// @notice what an entity is, for the few game rules that are not data of its components.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tag {
+    Enemy,
+    PlayerBullet,
+    EnemyBullet,
+    FuelDepot,
+    Bridge,
+    PowerUp,
+    // a crate of ammunition for the limited weapon modes.
+    Supply,
+    // an animation on the river, it touches nothing.
+    Effect,
+}
+
+impl Tag {
+    /// @notice the objects on the water move down with the river, the bullets fly on their own.
+    pub fn scrolls(self) -> bool {
+        !matches!(self, Tag::PlayerBullet | Tag::EnemyBullet)
+    }
+
+    /// @notice touching the entity costs the boat a life.
+    pub fn harms_boat(self) -> bool {
+        matches!(self, Tag::Enemy | Tag::EnemyBullet | Tag::Bridge)
+    }
+
+    /// @notice the entity is gone once it touched the boat.
+    pub fn spent_on_contact(self) -> bool {
+        matches!(self, Tag::EnemyBullet | Tag::PowerUp | Tag::Supply)
+    }
+}
+
+/// @notice how a sprite is laid out from the position of its entity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    // the glyph printed at the position.
+    Inline,
+    // one character of the glyph per row, going down from the position.
+    Vertical,
+    // the glyph repeated from bank to bank of the row.
+    Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sprite {
+    pub glyph: String,
+    // the glyph in the ascii sprite set.
+    pub ascii: String,
+    // the colours come from the theme.
+    pub paint: Paint,
+    pub layout: Layout,
+}
+
+/// @notice one kind of component of every entity, indexed by the slot of the entity.
+/// @dev the handles are expected to be alive, see World::is_alive.
+#[derive(Clone, Debug)]
+pub struct Storage<T> {
+    slots: Vec<Option<T>>,
+}
+
+impl<T> Default for Storage<T> {
+    fn default() -> Self {
+        Storage { slots: Vec::new() }
+    }
+}
+
+impl<T> Storage<T> {
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        self.slots.get(entity.index()).and_then(Option::as_ref)
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        self.slots.get_mut(entity.index()).and_then(Option::as_mut)
+    }
+
+    pub fn insert(&mut self, entity: Entity, value: T) {
+        self.set(entity.index(), Some(value));
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        self.slots.get_mut(entity.index()).and_then(Option::take)
+    }
+
+    fn set(&mut self, index: usize, value: Option<T>) {
+        if self.slots.len() <= index {
+            self.slots.resize_with(index + 1, || None);
+        }
+        self.slots[index] = value;
+    }
+}
+
+/// @notice the components of an entity about to be spawned, the prefabs of the game objects.
+#[derive(Clone, Debug)]
+pub struct Components {
+    pub tag: Tag,
+    pub position: Location,
+    pub velocity: Velocity,
+    pub sprite: Sprite,
+    pub body: Body,
+    // hits the entity takes before it is destroyed, entities without health can not be shot.
+    pub health: Option<u8>,
+    pub enemy: Option<Enemy>,
+    // points for destroying the entity.
+    pub score_value: Option<u32>,
+    // the power-up the boat gets by touching the entity.
+    pub power_up: Option<PowerUpKind>,
+    // a bullet that goes through what it destroys.
+    pub piercing: bool,
+    // the animation the sprite plays, the entity is removed once it is over.
+    pub animation: Option<Animation>,
+    // the player who shot the bullet, the points of what it hits go to them.
+    pub owner: Option<usize>,
+}
+
+impl Components {
+    pub fn enemy(kind: EnemyKind, location: Location, direction: f32) -> Self {
+        let behaviour = kind.behaviour();
+        Components {
+            tag: Tag::Enemy,
+            position: location,
+            velocity: Velocity::default(),
+            sprite: Sprite {
+                glyph: behaviour.glyph().to_string(),
+                ascii: behaviour.ascii().to_string(),
+                paint: Paint::Water,
+                layout: Layout::Inline,
+            },
+            body: Body::Cells(behaviour.hitbox()),
+            health: Some(1),
+            enemy: Some(Enemy { kind, direction }),
+            score_value: Some(behaviour.points()),
+            power_up: None,
+            piercing: false,
+            animation: None,
+            owner: None,
+        }
+    }
+
+    /// @notice a bullet of a boat, climbing the river.
+    pub fn player_bullet(location: Location, owner: usize) -> Self {
+        Components {
+            tag: Tag::PlayerBullet,
+            position: location,
+            velocity: Velocity::new(-BULLET_SPEED, 0.0),
+            sprite: Sprite {
+                glyph: '🔥'.to_string(),
+                ascii: "||".to_string(),
+                paint: Paint::Water,
+                layout: Layout::Inline,
+            },
+            body: Body::Cells(Hitbox {
+                width: 2,
+                height: 1,
+            }),
+            health: None,
+            enemy: None,
+            score_value: None,
+            power_up: None,
+            piercing: false,
+            animation: None,
+            owner: Some(owner),
+        }
+    }
+
+    pub fn enemy_bullet(location: Location, velocity: Velocity) -> Self {
+        Components {
+            tag: Tag::EnemyBullet,
+            position: location,
+            velocity,
+            sprite: Sprite {
+                glyph: '•'.to_string(),
+                ascii: "o".to_string(),
+                paint: Paint::EnemyBullet,
+                layout: Layout::Inline,
+            },
+            body: Body::Cells(Hitbox {
+                width: 1,
+                height: 1,
+            }),
+            health: None,
+            enemy: None,
+            score_value: None,
+            power_up: None,
+            piercing: false,
+            animation: None,
+            owner: None,
+        }
+    }
+
+    /// @notice a fuel depot, the boat refuels while hovering over it and it can be shot for points.
+    pub fn fuel_depot(location: Location) -> Self {
+        let glyph = "FUEL";
+        Components {
+            tag: Tag::FuelDepot,
+            position: location,
+            velocity: Velocity::default(),
+            sprite: Sprite {
+                glyph: glyph.to_string(),
+                ascii: glyph.to_string(),
+                paint: Paint::FuelDepot,
+                layout: Layout::Vertical,
+            },
+            body: Body::Cells(Hitbox {
+                width: 1,
+                height: glyph.len() as u16,
+            }),
+            health: Some(1),
+            enemy: None,
+            score_value: Some(FUEL_DEPOT_POINTS),
+            power_up: None,
+            piercing: false,
+            animation: None,
+            owner: None,
+        }
+    }
+
+    /// @notice a bridge spans the whole river at its row and blocks the boat until it is shot.
+    pub fn bridge(location: Location) -> Self {
+        Components {
+            tag: Tag::Bridge,
+            position: location,
+            velocity: Velocity::default(),
+            sprite: Sprite {
+                glyph: '='.to_string(),
+                ascii: "=".to_string(),
+                paint: Paint::Bridge,
+                layout: Layout::Span,
+            },
+            body: Body::Span,
+            health: Some(1),
+            enemy: None,
+            score_value: Some(BRIDGE_BONUS),
+            power_up: None,
+            piercing: false,
+            animation: None,
+            owner: None,
+        }
+    }
+
+    /// @notice a power-up floating on the river, the boat collects it by touching it.
+    pub fn power_up(kind: PowerUpKind, location: Location) -> Self {
+        Components {
+            tag: Tag::PowerUp,
+            position: location,
+            velocity: Velocity::default(),
+            sprite: Sprite {
+                glyph: kind.glyph().to_string(),
+                ascii: kind.ascii().to_string(),
+                paint: Paint::Water,
+                layout: Layout::Inline,
+            },
+            body: Body::Cells(Hitbox {
+                width: 2,
+                height: 1,
+            }),
+            health: None,
+            enemy: None,
+            score_value: None,
+            power_up: Some(kind),
+            piercing: false,
+            animation: None,
+            owner: None,
+        }
+    }
+
+    /// @notice an animation at a location of the river, i.e. an explosion.
+    pub fn effect(kind: AnimationKind, location: Location) -> Self {
+        let animation = Animation::new(kind);
+        let (glyph, ascii) = animation.frame();
+        Components {
+            tag: Tag::Effect,
+            position: location,
+            velocity: Velocity::default(),
+            sprite: Sprite {
+                glyph: glyph.to_string(),
+                ascii: ascii.to_string(),
+                paint: Paint::Water,
+                layout: Layout::Inline,
+            },
+            body: Body::Cells(Hitbox {
+                width: 2,
+                height: 1,
+            }),
+            health: None,
+            enemy: None,
+            score_value: None,
+            power_up: None,
+            piercing: false,
+            animation: Some(animation),
+            owner: None,
+        }
+    }
+
+    /// @notice a crate floating on the river, the boat resupplies its gun by touching it.
+    pub fn supply_crate(location: Location) -> Self {
+        Components {
+            tag: Tag::Supply,
+            position: location,
+            velocity: Velocity::default(),
+            sprite: Sprite {
+                glyph: '📦'.to_string(),
+                ascii: "[]".to_string(),
+                paint: Paint::Water,
+                layout: Layout::Inline,
+            },
+            body: Body::Cells(Hitbox {
+                width: 2,
+                height: 1,
+            }),
+            health: None,
+            enemy: None,
+            score_value: None,
+            power_up: None,
+            piercing: false,
+            animation: None,
+            owner: None,
+        }
+    }
+}
+
+/// @notice every object on the river, stored as columns of components.
+/// @dev the systems working on the components live in systems.rs.
+#[derive(Clone, Debug, Default)]
+pub struct World {
+    generations: Vec<u32>,
+    alive: Vec<bool>,
+    free: Vec<u32>,
+    pub tag: Storage<Tag>,
+    pub position: Storage<Location>,
+    pub velocity: Storage<Velocity>,
+    pub sprite: Storage<Sprite>,
+    pub body: Storage<Body>,
+    pub health: Storage<u8>,
+    pub enemy: Storage<Enemy>,
+    pub score_value: Storage<u32>,
+    pub power_up: Storage<PowerUpKind>,
+    pub piercing: Storage<()>,
+    pub animation: Storage<Animation>,
+    pub owner: Storage<usize>,
+}
+
+impl World {
+    pub fn spawn(&mut self, components: Components) -> Entity {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.generations.push(0);
+                self.alive.push(false);
+                (self.generations.len() - 1) as u32
+            }
+        };
+        self.alive[index as usize] = true;
+
+        let entity = Entity {
+            index,
+            generation: self.generations[index as usize],
+        };
+        let slot = entity.index();
+        self.tag.set(slot, Some(components.tag));
+        self.position.set(slot, Some(components.position));
+        self.velocity.set(slot, Some(components.velocity));
+        self.sprite.set(slot, Some(components.sprite));
+        self.body.set(slot, Some(components.body));
+        self.health.set(slot, components.health);
+        self.enemy.set(slot, components.enemy);
+        self.score_value.set(slot, components.score_value);
+        self.power_up.set(slot, components.power_up);
+        self.piercing.set(slot, components.piercing.then_some(()));
+        self.animation.set(slot, components.animation);
+        self.owner.set(slot, components.owner);
+        entity
+    }
+
+    /// @notice removes the entity and all of its components, stale handles are ignored.
+    pub fn despawn(&mut self, entity: Entity) {
+        if !self.is_alive(entity) {
+            return;
+        }
+
+        let slot = entity.index();
+        self.tag.set(slot, None);
+        self.position.set(slot, None);
+        self.velocity.set(slot, None);
+        self.sprite.set(slot, None);
+        self.body.set(slot, None);
+        self.health.set(slot, None);
+        self.enemy.set(slot, None);
+        self.score_value.set(slot, None);
+        self.power_up.set(slot, None);
+        self.piercing.set(slot, None);
+        self.animation.set(slot, None);
+        self.owner.set(slot, None);
+
+        self.alive[slot] = false;
+        self.generations[slot] = self.generations[slot].wrapping_add(1);
+        self.free.push(entity.index);
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.alive.get(entity.index()) == Some(&true)
+            && self.generations[entity.index()] == entity.generation
+    }
+
+    /// @notice the living entities in the order of their slots.
+    pub fn entities(&self) -> Vec<Entity> {
+        self.alive
+            .iter()
+            .enumerate()
+            .filter(|(_, alive)| **alive)
+            .map(|(index, _)| Entity {
+                index: index as u32,
+                generation: self.generations[index],
+            })
+            .collect()
+    }
+
+    pub fn tagged(&self, tag: Tag) -> Vec<Entity> {
+        self.entities()
+            .into_iter()
+            .filter(|&entity| self.tag.get(entity) == Some(&tag))
+            .collect()
+    }
+
+    pub fn count(&self, tag: Tag) -> usize {
+        self.tagged(tag).len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.alive.iter().filter(|alive| **alive).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        for entity in self.entities() {
+            self.despawn(entity);
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use crate::ecs::Components;
+use crate::{Enemy, Location, Velocity};
+use rand::Rng;
+
+/// @notice the kinds of enemies that can appear on the river.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnemyKind {
+    Ship,
+    Helicopter,
+    Jet,
+    Mine,
+}
+
+/// @notice the cells an enemy occupies, starting from its location.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hitbox {
+    pub width: u16,
+    pub height: u16,
+}
+
+/// @notice how an enemy shoots at the boat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FirePattern {
+    Silent,
+    // a single shot straight down the river.
+    Straight,
+    // a single shot heading to where the boat is.
+    Aimed,
+    // three shots fanning out.
+    Spread,
+}
+
+/// @notice what an enemy can see of the world while it moves during a tick.
+#[derive(Clone, Copy, Debug)]
+pub struct BehaviourContext {
+    // the (left bank, right bank) of the enemy row.
+    pub banks: (u16, u16),
+    pub max_screen_i: u16,
+    pub tick: u32,
+}
+
+/// @notice the per kind rules of an enemy.
+/// @dev advance steers the enemy by setting its velocity, which the movement system integrates right after.
+/// the river scrolling moves every enemy down on top of that.
+pub trait EnemyBehaviour {
+    fn advance(
+        &self,
+        enemy: &mut Enemy,
+        location: &Location,
+        velocity: &mut Velocity,
+        ctx: &BehaviourContext,
+    );
+    fn hitbox(&self) -> Hitbox;
+    fn points(&self) -> u32;
+    fn glyph(&self) -> char;
+    // the glyph in the ascii sprite set, as wide as the hitbox.
+    fn ascii(&self) -> &'static str;
+    fn fire_pattern(&self) -> FirePattern;
+}
+
+/// @notice patrols between the banks, turning around when it reaches one.
+pub struct Ship;
+/// @notice stays still for a while and then darts sideways.
+pub struct Helicopter;
+/// @notice crosses the whole screen, flying over the banks.
+pub struct Jet;
+/// @notice never moves by itself.
+pub struct Mine;
+
+const SHIP_SPEED: f32 = 0.5;
+const HELICOPTER_DART_EVERY: u32 = 8;
+const HELICOPTER_DART_TICKS: u32 = 2;
+const HELICOPTER_DART_SPEED: f32 = 2.0;
+const JET_SPEED: f32 = 2.5;
+
+// whether an enemy of the given width fits in the water at the given column.
+fn in_water(column: f32, width: u16, banks: (u16, u16)) -> bool {
+    column > banks.0 as f32 && column + (width as f32) < banks.1 as f32
+}
+
+impl EnemyBehaviour for Ship {
+    fn advance(
+        &self,
+        enemy: &mut Enemy,
+        location: &Location,
+        velocity: &mut Velocity,
+        ctx: &BehaviourContext,
+    ) {
+        let next = location.element_j + enemy.direction * SHIP_SPEED;
+        if !in_water(next, self.hitbox().width, ctx.banks) {
+            enemy.direction = -enemy.direction;
+        }
+        velocity.dj = enemy.direction * SHIP_SPEED;
+    }
+
+    fn hitbox(&self) -> Hitbox {
+        // as wide as the 🚢 on the screen.
+        Hitbox {
+            width: 2,
+            height: 1,
+        }
+    }
+
+    fn points(&self) -> u32 {
+        30
+    }
+
+    fn glyph(&self) -> char {
+        '🚢'
+    }
+
+    fn ascii(&self) -> &'static str {
+        "<>"
+    }
+
+    fn fire_pattern(&self) -> FirePattern {
+        FirePattern::Straight
+    }
+}
+
+impl EnemyBehaviour for Helicopter {
+    fn advance(
+        &self,
+        enemy: &mut Enemy,
+        location: &Location,
+        velocity: &mut Velocity,
+        ctx: &BehaviourContext,
+    ) {
+        let phase = ctx.tick % HELICOPTER_DART_EVERY;
+
+        // zigzag between the banks, every dart goes the other way.
+        if phase == HELICOPTER_DART_TICKS {
+            enemy.direction = -enemy.direction;
+        }
+
+        let next = location.element_j + enemy.direction * HELICOPTER_DART_SPEED;
+        velocity.dj =
+            if phase < HELICOPTER_DART_TICKS && in_water(next, self.hitbox().width, ctx.banks) {
+                enemy.direction * HELICOPTER_DART_SPEED
+            } else {
+                0.0
+            };
+    }
+
+    fn hitbox(&self) -> Hitbox {
+        Hitbox {
+            width: 2,
+            height: 1,
+        }
+    }
+
+    fn points(&self) -> u32 {
+        60
+    }
+
+    fn glyph(&self) -> char {
+        '🚁'
+    }
+
+    fn ascii(&self) -> &'static str {
+        "-+"
+    }
+
+    fn fire_pattern(&self) -> FirePattern {
+        FirePattern::Aimed
+    }
+}
+
+impl EnemyBehaviour for Jet {
+    fn advance(
+        &self,
+        enemy: &mut Enemy,
+        location: &Location,
+        velocity: &mut Velocity,
+        ctx: &BehaviourContext,
+    ) {
+        // the jet stops at the edge of the screen.
+        let next = location.element_j + enemy.direction * JET_SPEED;
+        velocity.dj = next.clamp(0.0, ctx.max_screen_i as f32) - location.element_j;
+    }
+
+    fn hitbox(&self) -> Hitbox {
+        Hitbox {
+            width: 2,
+            height: 1,
+        }
+    }
+
+    fn points(&self) -> u32 {
+        100
+    }
+
+    fn glyph(&self) -> char {
+        '🛩'
+    }
+
+    fn ascii(&self) -> &'static str {
+        "=>"
+    }
+
+    fn fire_pattern(&self) -> FirePattern {
+        FirePattern::Spread
+    }
+}
+
+impl EnemyBehaviour for Mine {
+    fn advance(
+        &self,
+        _enemy: &mut Enemy,
+        _location: &Location,
+        _velocity: &mut Velocity,
+        _ctx: &BehaviourContext,
+    ) {
+    }
+
+    fn hitbox(&self) -> Hitbox {
+        Hitbox {
+            width: 2,
+            height: 1,
+        }
+    }
+
+    fn points(&self) -> u32 {
+        10
+    }
+
+    fn glyph(&self) -> char {
+        '💣'
+    }
+
+    fn ascii(&self) -> &'static str {
+        "()"
+    }
+
+    fn fire_pattern(&self) -> FirePattern {
+        FirePattern::Silent
+    }
+}
+
+// cells an enemy bullet travels per tick.
+const ENEMY_BULLET_SPEED: f32 = 1.5;
+// columns per row of the outer shots of a spread, of the enemies' and of the boat's.
+pub const SPREAD_SLOPE: f32 = 0.5;
+
+impl FirePattern {
+    /// @notice the bullets an enemy standing at `from` shoots towards the boat at `target`.
+    pub fn fire(self, from: &Location, target: &Location) -> Vec<Components> {
+        let velocities: Vec<Velocity> = match self {
+            FirePattern::Silent => vec![],
+            FirePattern::Straight => vec![Velocity::new(ENEMY_BULLET_SPEED, 0.0)],
+            FirePattern::Aimed => {
+                let (di, dj) = (
+                    target.element_i - from.element_i,
+                    target.element_j - from.element_j,
+                );
+                if di <= 0.0 {
+                    // the boat is behind the enemy, nothing to aim at.
+                    return vec![];
+                }
+                let length = di.hypot(dj);
+                vec![Velocity::new(
+                    di / length * ENEMY_BULLET_SPEED,
+                    dj / length * ENEMY_BULLET_SPEED,
+                )]
+            }
+            FirePattern::Spread => [-SPREAD_SLOPE, 0.0, SPREAD_SLOPE]
+                .iter()
+                .map(|slope| Velocity::new(ENEMY_BULLET_SPEED, slope * ENEMY_BULLET_SPEED))
+                .collect(),
+        };
+
+        velocities
+            .into_iter()
+            .map(|velocity| {
+                Components::enemy_bullet(
+                    Location::new(from.element_i + 1.0, from.element_j),
+                    velocity,
+                )
+            })
+            .collect()
+    }
+}
+
+impl EnemyKind {
+    pub const ALL: [EnemyKind; 4] = [
+        EnemyKind::Ship,
+        EnemyKind::Helicopter,
+        EnemyKind::Jet,
+        EnemyKind::Mine,
+    ];
+
+    pub fn behaviour(self) -> &'static dyn EnemyBehaviour {
+        match self {
+            EnemyKind::Ship => &Ship,
+            EnemyKind::Helicopter => &Helicopter,
+            EnemyKind::Jet => &Jet,
+            EnemyKind::Mine => &Mine,
+        }
+    }
+
+    /// @notice picks a kind using the given weights, in the order of EnemyKind::ALL.
+    pub fn pick<R: Rng>(rng: &mut R, weights: [u32; 4]) -> EnemyKind {
+        let total: u32 = weights.iter().sum();
+        if total == 0 {
+            return EnemyKind::Ship;
+        }
+
+        let mut roll = rng.gen_range(0..total);
+        for (kind, weight) in EnemyKind::ALL.iter().zip(weights) {
+            if roll < weight {
+                return *kind;
+            }
+            roll -= weight;
+        }
+        EnemyKind::Ship
+    }
+}
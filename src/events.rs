@@ -0,0 +1,35 @@
+use crate::{EnemyKind, PowerUpKind};
+use serde::{Deserialize, Serialize};
+
+/// @notice what happened during a tick, for the systems that follow the game without driving it.
+/// @dev the events are handed to the observers at the end of Game2DMatrix::reactions and cleared,
+/// what the player does between two ticks (a shot) belongs to the next one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameEvent {
+    // a tick of the given length went by.
+    Tick { ms: u64 },
+    RowTravelled,
+    // the trigger was pulled, a spread shot fires several bullets at once.
+    ShotFired { bullets: u32 },
+    // a bullet of the player hit something, whether or not it was destroyed.
+    BulletHit,
+    EnemyKilled(EnemyKind),
+    BridgeDestroyed,
+    DepotShot,
+    // the fuel the tank actually gained.
+    Refuelled { fuel: u32 },
+    PowerUpCollected(PowerUpKind),
+    // columns between the boat and the closest bank, sent every tick the boat is on the river.
+    BankClearance(f32),
+    LifeLost(DeathCause),
+}
+
+/// @notice what took a life of the player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeathCause {
+    Bank,
+    Enemy,
+    EnemyBullet,
+    Bridge,
+    OutOfFuel,
+}
@@ -0,0 +1,74 @@
+use crate::theme::Paint;
+use crate::{Game2DMatrix, GameSettings, GameStatus, Replay};
+use crossterm::{
+    cursor::MoveTo,
+    style::{Print, ResetColor},
+    QueueableCommand,
+};
+use std::io::{Result, Stdout};
+
+/// @notice a recorded run raced against, its boat sails as a ghost beside the live one.
+/// @dev the run is played back from its replay tick by tick with the live game, muted. the live
+/// game has to be on the same seed and rules, see `Ghost::settings`.
+pub struct Ghost {
+    pub replay: Replay,
+    pub game: Game2DMatrix,
+}
+
+impl Ghost {
+    pub fn new(replay: Replay, base: &GameSettings) -> Self {
+        let mut game =
+            Game2DMatrix::with_screen(replay.settings(base), replay.screen.0, replay.screen.1);
+        game.muted = true;
+        Ghost { replay, game }
+    }
+
+    /// @notice the settings of the live game racing the ghost, the rest of them come from `base`.
+    pub fn settings(&self, base: &GameSettings) -> GameSettings {
+        self.replay.settings(base)
+    }
+
+    pub fn initialize(&mut self, screen: &mut Stdout) -> Result<()> {
+        self.game.initialize_ground(screen)
+    }
+
+    /// @notice plays the next tick of the recorded run, until the run is over.
+    pub fn step(&mut self) -> Result<()> {
+        if self.game.game_staus != GameStatus::ALIVE {
+            return Ok(());
+        }
+        for (p, action) in self.replay.actions_at(self.game.tick) {
+            self.game.apply(p, action);
+        }
+        self.game.shift_ground_loc()?;
+        self.game.reactions()
+    }
+
+    /// @notice the live score ahead of the ghost's score at the same tick, negative when behind.
+    pub fn delta(&self, live: &Game2DMatrix) -> i64 {
+        live.score() as i64 - self.game.score() as i64
+    }
+
+    /// @notice draws the boats of the ghost on the live river and the score delta at the top right.
+    pub fn draw(&self, screen: &mut Stdout, live: &Game2DMatrix) -> Result<()> {
+        for player in &self.game.players {
+            live.draw_ghost(screen, player, self.game.distance, 'G')?;
+        }
+
+        let delta = self.delta(live);
+        let line = format!(" Ghost {:>8}  {:>+7} ", self.game.score(), delta);
+        let column = live
+            .max_screen_i
+            .saturating_sub(line.chars().count() as u16 + 1);
+        screen
+            .queue(MoveTo(column, 1))?
+            .queue(live.theme.paint(if delta < 0 {
+                Paint::GaugeLow
+            } else {
+                Paint::GaugeHigh
+            }))?
+            .queue(Print(line))?
+            .queue(ResetColor)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{DifficultyPreset, Game2DMatrix};
+use serde::{Deserialize, Serialize};
+
+// where the local high scores are kept.
+pub const HIGH_SCORES_DB: &str = "high_scores_db";
+const TABLE_KEY: &str = "table";
+// entries kept per table, the free runs and every daily challenge have their own table.
+pub const TABLE_SIZE: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: u32,
+    pub rows_travelled: u32,
+    pub enemies_killed: u32,
+    pub difficulty: DifficultyPreset,
+    // unix timestamp of the end of the run.
+    pub recorded_at: i64,
+    // the seed of the daily challenge, none for a free run.
+    pub daily: Option<u64>,
+    // the path of the replay of the run.
+    pub replay: Option<String>,
+}
+
+impl HighScore {
+    pub fn new(game: &Game2DMatrix, daily: Option<u64>, replay: Option<String>) -> Self {
+        HighScore {
+            score: game.score(),
+            rows_travelled: game.stats.rows_travelled,
+            enemies_killed: game.enemy_killed(),
+            difficulty: game.settings.difficulty,
+            recorded_at: chrono::Utc::now().timestamp(),
+            daily,
+            replay,
+        }
+    }
+}
+
+/// @notice the best runs, the highest score first.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct HighScores {
+    pub entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// @notice the table of the free runs, or of a daily challenge.
+    pub fn table(&self, daily: Option<u64>) -> impl Iterator<Item = &HighScore> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.daily == daily)
+    }
+
+    /// @notice adds a run, returns its rank in its table when it made it.
+    pub fn insert(&mut self, entry: HighScore) -> Option<usize> {
+        let daily = entry.daily;
+        let rank = self
+            .table(daily)
+            .filter(|other| other.score >= entry.score)
+            .count();
+        if rank >= TABLE_SIZE {
+            return None;
+        }
+
+        let at = self
+            .entries
+            .partition_point(|other| other.score >= entry.score);
+        self.entries.insert(at, entry);
+
+        // the run pushed the last entry of its table out.
+        if let Some(last) = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, other)| other.daily == daily)
+            .nth(TABLE_SIZE)
+            .map(|(k, _)| k)
+        {
+            self.entries.remove(last);
+        }
+        Some(rank)
+    }
+}
+
+/// @notice the local sled storage of the high scores.
+pub struct HighScoreStore {
+    db: sled::Db,
+}
+
+impl HighScoreStore {
+    pub fn open(path: &str) -> sled::Result<Self> {
+        Ok(HighScoreStore {
+            db: sled::open(path)?,
+        })
+    }
+
+    pub fn load(&self) -> sled::Result<HighScores> {
+        Ok(self
+            .db
+            .get(TABLE_KEY)?
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default())
+    }
+
+    pub fn save(&self, high_scores: &HighScores) -> sled::Result<()> {
+        let bytes = serde_json::to_vec(high_scores).expect("the high scores serialize");
+        self.db.insert(TABLE_KEY, bytes)?;
+        self.db.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(score: u32, daily: Option<u64>) -> HighScore {
+        HighScore {
+            score,
+            rows_travelled: 0,
+            enemies_killed: 0,
+            difficulty: DifficultyPreset::Normal,
+            recorded_at: 0,
+            daily,
+            replay: None,
+        }
+    }
+
+    fn scores(high_scores: &HighScores, daily: Option<u64>) -> Vec<u32> {
+        high_scores.table(daily).map(|entry| entry.score).collect()
+    }
+
+    #[test]
+    fn runs_are_ranked_by_score() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(high_scores.insert(run(300, None)), Some(0));
+        assert_eq!(high_scores.insert(run(500, None)), Some(0));
+        assert_eq!(high_scores.insert(run(400, None)), Some(1));
+        // a tie ranks after the runs already in the table.
+        assert_eq!(high_scores.insert(run(400, None)), Some(2));
+        assert_eq!(scores(&high_scores, None), [500, 400, 400, 300]);
+    }
+
+    #[test]
+    fn a_full_table_keeps_the_best_runs() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=TABLE_SIZE as u32 {
+            high_scores.insert(run(score * 100, None));
+        }
+        assert_eq!(high_scores.insert(run(50, None)), None);
+        assert_eq!(high_scores.insert(run(550, None)), Some(5));
+
+        let table = scores(&high_scores, None);
+        assert_eq!(table.len(), TABLE_SIZE);
+        assert_eq!(table.first(), Some(&1000));
+        assert_eq!(table.last(), Some(&200));
+    }
+
+    #[test]
+    fn every_daily_challenge_has_its_own_table() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=TABLE_SIZE as u32 {
+            high_scores.insert(run(score * 100, None));
+        }
+        assert_eq!(high_scores.insert(run(10, Some(20261019))), Some(0));
+        assert_eq!(high_scores.insert(run(20, Some(20261020))), Some(0));
+
+        assert_eq!(scores(&high_scores, None).len(), TABLE_SIZE);
+        assert_eq!(scores(&high_scores, Some(20261019)), [10]);
+    }
+}
@@ -0,0 +1,316 @@
+use crate::theme::Paint;
+use crate::{Game2DMatrix, WeaponMode, BRIDGE_INTERVAL};
+use crossterm::{cursor::MoveTo, style::Print, style::ResetColor, QueueableCommand};
+use std::io::{Result, Stdout};
+
+/// @notice columns on the left of the screen taken by the HUD panel, the river flows right of it.
+pub const HUD_WIDTH: u16 = 24;
+// cells of the fuel and heat gauges.
+const GAUGE_CELLS: usize = 16;
+// the minimap shows the current section of the river and the ones after it.
+const MINIMAP_SECTIONS: u32 = 3;
+const MINIMAP_ROWS: u16 = 12;
+// columns of the minimap inside the panel.
+const MINIMAP_WIDTH: u16 = HUD_WIDTH - 4;
+
+/// @notice how full a gauge is, the colour of the gauge changes with it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GaugeLevel {
+    High,
+    Mid,
+    Low,
+}
+
+impl GaugeLevel {
+    /// @notice the level of the fuel tank, low from the fuel warning level and mid below half a tank.
+    pub fn fuel(gas: u32, capacity: u32, low_level: u32) -> Self {
+        if gas <= low_level {
+            GaugeLevel::Low
+        } else if gas * 2 < capacity {
+            GaugeLevel::Mid
+        } else {
+            GaugeLevel::High
+        }
+    }
+
+    pub fn paint(self) -> Paint {
+        match self {
+            GaugeLevel::High => Paint::GaugeHigh,
+            GaugeLevel::Mid => Paint::GaugeMid,
+            GaugeLevel::Low => Paint::GaugeLow,
+        }
+    }
+}
+
+/// @notice the cells of a gauge filled at `ratio` (0 to 1), the filled and the empty ones.
+pub fn gauge_cells(ratio: f32) -> (usize, usize) {
+    let filled = ((ratio.clamp(0.0, 1.0) * GAUGE_CELLS as f32).round() as usize).min(GAUGE_CELLS);
+    (filled, GAUGE_CELLS - filled)
+}
+
+/// @notice the section of the river, a new one starts after every bridge.
+pub fn section(distance: u32) -> u32 {
+    distance / BRIDGE_INTERVAL + 1
+}
+
+impl Game2DMatrix {
+    /// @notice draws the HUD panel left of the river, the scores, the gauges, the power-ups and the minimap.
+    pub fn draw_hud(&self, screen: &mut Stdout) -> Result<()> {
+        let panel = self.theme.paint(Paint::Hud);
+        let blank = " ".repeat(HUD_WIDTH as usize);
+        for row in 0..self.max_screen_j {
+            screen
+                .queue(MoveTo(0, row))?
+                .queue(panel)?
+                .queue(Print(&blank))?;
+        }
+
+        let mut row = 1;
+        for line in [
+            format!("Section {:>10}", section(self.distance)),
+            format!("Speed {:>11.2}x", self.throttle),
+        ] {
+            screen.queue(MoveTo(1, row))?.queue(Print(line))?;
+            row += 1;
+        }
+        row += 1;
+
+        for p in 0..self.players.len() {
+            row = self.draw_player_panel(screen, p, row)?;
+        }
+
+        // the minimap sits at the bottom of the panel when there is room left for it.
+        let minimap_top = self.max_screen_j.saturating_sub(MINIMAP_ROWS + 4);
+        if minimap_top > row {
+            self.draw_minimap(screen, minimap_top)?;
+        }
+
+        if let Some((achievement, ticks)) = self.achievement_notice {
+            if ticks > 0 {
+                screen
+                    .queue(MoveTo(1, self.max_screen_j - 2))?
+                    .queue(self.theme.paint(Paint::Notice))?
+                    .queue(Print(format!(
+                        "{} {}",
+                        self.theme.glyph("🏆", "*"),
+                        achievement
+                            .name
+                            .chars()
+                            .take(HUD_WIDTH as usize - 5)
+                            .collect::<String>()
+                    )))?;
+            }
+        }
+
+        screen.queue(ResetColor)?;
+        Ok(())
+    }
+
+    // the score, the lives, the fuel, the gun and the power-ups of a player from a row of the panel,
+    // returns the row after them.
+    fn draw_player_panel(&self, screen: &mut Stdout, p: usize, mut row: u16) -> Result<u16> {
+        let player = &self.players[p];
+        let panel = self.theme.paint(Paint::Hud);
+
+        // the boats of a co-op game are told apart by their logo.
+        if self.players.len() > 1 {
+            screen
+                .queue(MoveTo(1, row))?
+                .queue(Print(format!("P{} {}", p + 1, player.logo)))?;
+            row += 1;
+        }
+
+        let lines = [
+            format!("Score {:>12}", player.score),
+            match player.scoring.streak {
+                0 | 1 => format!("x{:.2}", player.scoring.multiplier(&self.settings.scoring)),
+                streak => format!(
+                    "x{:.2} ({} streak)",
+                    player.scoring.multiplier(&self.settings.scoring),
+                    streak
+                ),
+            },
+            format!("Enemy killed {:>5}", player.enemy_killed),
+            format!("Lives {}", player.logo.repeat(player.lives as usize)),
+        ];
+        for line in lines {
+            screen.queue(MoveTo(1, row))?.queue(Print(line))?;
+            row += 1;
+        }
+        row += 1;
+
+        // the fuel gauge, with the fuel warning blinking under it.
+        let fuel = GaugeLevel::fuel(
+            player.gas,
+            self.settings.fuel_capacity,
+            self.settings.fuel_low_level,
+        );
+        screen.queue(MoveTo(1, row))?.queue(Print(format!(
+            "Fuel {:>12}",
+            format!("{}/{}", player.gas, self.settings.fuel_capacity)
+        )))?;
+        self.draw_gauge(
+            screen,
+            row + 1,
+            player.gas as f32 / self.settings.fuel_capacity.max(1) as f32,
+            fuel,
+        )?;
+        if player.fuel_low_warned && self.tick % 8 < 4 {
+            screen
+                .queue(MoveTo(1, row + 2))?
+                .queue(self.theme.paint(Paint::Alert))?
+                .queue(Print("FUEL LOW"))?
+                .queue(ResetColor)?
+                .queue(panel)?;
+        }
+        row += 3;
+
+        // the gun in the limited modes.
+        match self.settings.weapon_mode {
+            WeaponMode::Unlimited => {}
+            WeaponMode::Ammo => {
+                let paint = if self.weapon_ready(p) {
+                    Paint::Hud
+                } else {
+                    Paint::Alert
+                };
+                screen
+                    .queue(MoveTo(1, row))?
+                    .queue(self.theme.paint(paint))?
+                    .queue(Print(format!(
+                        "Ammo {:>12}",
+                        format!("{}/{}", player.ammo, self.settings.ammo_capacity)
+                    )))?
+                    .queue(ResetColor)?
+                    .queue(panel)?;
+                row += 2;
+            }
+            WeaponMode::Heat => {
+                let heat = player.heat / self.settings.max_heat;
+                let level = if player.overheated {
+                    GaugeLevel::Low
+                } else if heat > 0.5 {
+                    GaugeLevel::Mid
+                } else {
+                    GaugeLevel::High
+                };
+                screen
+                    .queue(MoveTo(1, row))?
+                    .queue(Print(if player.overheated {
+                        "Heat      OVERHEAT"
+                    } else {
+                        "Heat"
+                    }))?;
+                self.draw_gauge(screen, row + 1, heat, level)?;
+                row += 3;
+            }
+        }
+
+        // the active power-ups with the seconds they have left.
+        for (kind, ticks) in player.power_ups.active() {
+            let seconds = ticks as f32 * self.stage.tick_ms as f32 / 1000.0;
+            screen.queue(MoveTo(1, row))?.queue(Print(format!(
+                "{} {}: {:.1}s",
+                self.theme.glyph(&kind.glyph().to_string(), kind.ascii()),
+                kind.label(),
+                seconds
+            )))?;
+            row += 1;
+        }
+        Ok(row + 1)
+    }
+
+    // a gauge on its own line of the panel, the empty cells stay visible without colours.
+    fn draw_gauge(
+        &self,
+        screen: &mut Stdout,
+        row: u16,
+        ratio: f32,
+        level: GaugeLevel,
+    ) -> Result<()> {
+        let (filled, empty) = gauge_cells(ratio);
+        screen
+            .queue(MoveTo(1, row))?
+            .queue(Print("["))?
+            .queue(self.theme.paint(level.paint()))?
+            .queue(Print(self.theme.glyph("█", "#").repeat(filled)))?
+            .queue(Print(self.theme.glyph("░", "-").repeat(empty)))?
+            .queue(ResetColor)?
+            .queue(self.theme.paint(Paint::Hud))?
+            .queue(Print("]"))?;
+        Ok(())
+    }
+
+    /// @notice the river ahead, the coming sections with their banks and the bridges closing them.
+    /// @dev the banks ahead come from a copy of the river generator, each line shows the banks at its first
+    /// row. the bottom line is the river at the boat, with the boat on it.
+    fn draw_minimap(&self, screen: &mut Stdout, top: u16) -> Result<()> {
+        let rows_per_line = MINIMAP_SECTIONS * BRIDGE_INTERVAL / MINIMAP_ROWS as u32;
+        let river_columns = self.max_screen_i.saturating_sub(HUD_WIDTH).max(1) as u32;
+        let scale = |column: u16| {
+            ((column.saturating_sub(HUD_WIDTH) as u32 * MINIMAP_WIDTH as u32 / river_columns)
+                as u16)
+                .min(MINIMAP_WIDTH)
+        };
+        let bank = self.theme.bank_fill().to_string();
+        let river_ahead = self.river_ahead(MINIMAP_ROWS as u32 * rows_per_line);
+
+        screen
+            .queue(MoveTo(1, top))?
+            .queue(self.theme.paint(Paint::Hud))?
+            .queue(Print("Ahead"))?;
+
+        for line in 0..MINIMAP_ROWS {
+            // the furthest rows at the top.
+            let ahead = (MINIMAP_ROWS - 1 - line) as u32;
+            let from = self.distance + ahead * rows_per_line;
+            let to = from + rows_per_line;
+            let row = top + 1 + line;
+
+            let (left, right) = if ahead == 0 {
+                self.ground[self.max_screen_j as usize - 10]
+            } else {
+                river_ahead[(ahead * rows_per_line) as usize - 1]
+            };
+            let (left, right) = (scale(left), scale(right));
+            // bridges are spawned on the rows at a multiple of the interval.
+            let bridge = ahead > 0 && (from..to).any(|row| row.is_multiple_of(BRIDGE_INTERVAL));
+
+            screen
+                .queue(MoveTo(2, row))?
+                .queue(self.theme.paint(Paint::Bank))?
+                .queue(Print(bank.repeat(left as usize)))?
+                .queue(
+                    self.theme
+                        .paint(if bridge { Paint::Bridge } else { Paint::Water }),
+                )?
+                .queue(Print(
+                    if bridge { "=" } else { " " }.repeat(right.saturating_sub(left) as usize),
+                ))?
+                .queue(self.theme.paint(Paint::Bank))?
+                .queue(Print(
+                    bank.repeat(MINIMAP_WIDTH.saturating_sub(right) as usize),
+                ))?;
+
+            if ahead == 0 {
+                for player in self.players.iter().filter(|player| player.is_sailing()) {
+                    screen
+                        .queue(MoveTo(2 + scale(player.i), row))?
+                        .queue(self.water_at(player.i, player.j as f32))?
+                        .queue(Print(self.theme.glyph("▲", "^")))?;
+                }
+            } else if bridge {
+                screen
+                    .queue(MoveTo(2 + MINIMAP_WIDTH, row))?
+                    .queue(ResetColor)?
+                    .queue(self.theme.paint(Paint::Hud))?
+                    .queue(Print(section(to)))?;
+            }
+        }
+
+        screen
+            .queue(ResetColor)?
+            .queue(self.theme.paint(Paint::Hud))?;
+        Ok(())
+    }
+}
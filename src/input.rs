@@ -0,0 +1,190 @@
+use crate::Game2DMatrix;
+use crossterm::event::KeyCode;
+use serde::{Deserialize, Serialize};
+
+/// @notice what the player can do to the boat, the only input of the simulation.
+/// @dev the actions are recorded per tick in the replays, see replay.rs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    Left,
+    Right,
+    Faster,
+    Slower,
+    Fire,
+}
+
+/// @notice what a key does in the menus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuKey {
+    Up,
+    Down,
+    Left,
+    Right,
+    Select,
+    Back,
+}
+
+/// @notice the keys of the game, the menus are navigated with the same ones.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyBindings {
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub faster: KeyCode,
+    pub slower: KeyCode,
+    pub fire: KeyCode,
+    pub achievements: KeyCode,
+    pub quit: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            left: KeyCode::Left,
+            right: KeyCode::Right,
+            faster: KeyCode::Up,
+            slower: KeyCode::Down,
+            fire: KeyCode::Char(' '),
+            achievements: KeyCode::Char('a'),
+            quit: KeyCode::Char('q'),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// @notice the keys of the second boat in the co-op mode, on the left of the keyboard.
+    /// @dev e s d f rather than w a s d, `a` is the achievements key of the game.
+    pub fn second_player() -> Self {
+        KeyBindings {
+            left: KeyCode::Char('s'),
+            right: KeyCode::Char('f'),
+            faster: KeyCode::Char('e'),
+            slower: KeyCode::Char('d'),
+            fire: KeyCode::Tab,
+            ..KeyBindings::default()
+        }
+    }
+
+    /// @notice the keys steering a boat.
+    pub fn action_keys(&self) -> [KeyCode; 5] {
+        [self.left, self.right, self.faster, self.slower, self.fire]
+    }
+
+    /// @notice every bound key, the boat's and the game's.
+    pub fn keys(&self) -> [KeyCode; 7] {
+        [
+            self.left,
+            self.right,
+            self.faster,
+            self.slower,
+            self.fire,
+            self.achievements,
+            self.quit,
+        ]
+    }
+
+    pub fn action(&self, key: KeyCode) -> Option<Action> {
+        match key {
+            key if key == self.left => Some(Action::Left),
+            key if key == self.right => Some(Action::Right),
+            key if key == self.faster => Some(Action::Faster),
+            key if key == self.slower => Some(Action::Slower),
+            key if key == self.fire => Some(Action::Fire),
+            _ => None,
+        }
+    }
+
+    /// @notice the menu meaning of a key, enter and escape always select and go back.
+    pub fn menu(&self, key: KeyCode) -> Option<MenuKey> {
+        match key {
+            KeyCode::Enter => Some(MenuKey::Select),
+            KeyCode::Esc => Some(MenuKey::Back),
+            key if key == self.faster => Some(MenuKey::Up),
+            key if key == self.slower => Some(MenuKey::Down),
+            key if key == self.left => Some(MenuKey::Left),
+            key if key == self.right => Some(MenuKey::Right),
+            key if key == self.fire => Some(MenuKey::Select),
+            key if key == self.quit => Some(MenuKey::Back),
+            _ => None,
+        }
+    }
+
+    /// @notice rebinds an action from a `name=key` pair, i.e. `fire=f` or `left=a`.
+    pub fn bind(&mut self, binding: &str) -> Result<(), String> {
+        let (name, key) = binding
+            .split_once('=')
+            .ok_or_else(|| format!("invalid binding `{binding}`, expected action=key"))?;
+        let key = parse_key(key)?;
+
+        let slot = match name.to_lowercase().as_str() {
+            "left" => &mut self.left,
+            "right" => &mut self.right,
+            "faster" => &mut self.faster,
+            "slower" => &mut self.slower,
+            "fire" => &mut self.fire,
+            "achievements" => &mut self.achievements,
+            "quit" => &mut self.quit,
+            other => {
+                return Err(format!(
+                    "unknown action `{other}`, expected left, right, faster, slower, fire, \
+                     achievements or quit"
+                ))
+            }
+        };
+        *slot = key;
+        Ok(())
+    }
+}
+
+/// @notice a key from its name, i.e. `space`, `up`, `enter` or a single character.
+pub fn parse_key(name: &str) -> Result<KeyCode, String> {
+    let key = match name.to_lowercase().as_str() {
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "space" => KeyCode::Char(' '),
+        "enter" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "esc" => KeyCode::Esc,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return Err(format!("unknown key `{name}`")),
+            }
+        }
+    };
+    Ok(key)
+}
+
+impl Game2DMatrix {
+    /// @notice applies an action of a player to their boat, the throttle of the river is shared.
+    /// @dev returns whether it did anything, a shot may wait for the gun and the boat for the screen edge.
+    pub fn apply(&mut self, p: usize, action: Action) -> bool {
+        // a wreck does not steer.
+        let Some(player) = self.players.get_mut(p).filter(|player| player.is_sailing()) else {
+            return false;
+        };
+
+        match action {
+            Action::Right if player.i + 1 < self.max_screen_i => {
+                player.i += 2;
+                true
+            }
+            Action::Left if player.i > 1 => {
+                player.i -= 2;
+                true
+            }
+            Action::Faster => {
+                self.change_throttle(1);
+                true
+            }
+            Action::Slower => {
+                self.change_throttle(-1);
+                true
+            }
+            Action::Fire => self.fire(p),
+            _ => false,
+        }
+    }
+}
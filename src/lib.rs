@@ -1,12 +1,12 @@
 use crossterm::{
     cursor::{Hide, MoveTo, Show},
     event::{poll, read, Event, KeyCode},
-    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    style::{Print, ResetColor},
     terminal::{enable_raw_mode, size, Clear, ClearType},
     ExecutableCommand, QueueableCommand,
 };
 use std::{
-    borrow::{Borrow, BorrowMut}, clone, default, io::{stdout, Result, Stdout, Write}, ops::{Deref, DerefMut}, thread::{self, sleep}, vec
+    borrow::{Borrow, BorrowMut}, clone, default, io::{stdout, Result, Stdout, Write}, ops::{Deref, DerefMut}, vec
 };
 
 use rodio::{buffer, source::Source, Decoder, OutputStream};
@@ -18,10 +18,56 @@ use ndarray::{Array, Array2};
 use rand::prelude::*;
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
 // use std::thread;
 
+pub mod achievements;
+pub mod animation;
+pub mod collision;
+pub mod difficulty;
+pub mod ecs;
+pub mod enemy;
+pub mod events;
+pub mod ghost;
+pub mod highscores;
+pub mod hud;
+pub mod input;
+pub mod menu;
+pub mod netplay;
+pub mod physics;
+pub mod player;
+pub mod powerup;
+pub mod replay;
+pub mod scoring;
+pub mod settings;
+pub mod spatial;
+pub mod spawn;
+pub mod stats;
+pub mod systems;
+pub mod theme;
+pub mod weapon;
+pub use achievements::{Achievement, AchievementStore, Achievements};
+pub use animation::{Animation, AnimationKind};
+pub use difficulty::{Difficulty, DifficultyPreset, Stage};
+pub use ecs::{Components, Entity, Tag, World};
+pub use enemy::{EnemyBehaviour, EnemyKind};
+pub use events::{DeathCause, GameEvent};
+pub use ghost::Ghost;
+pub use highscores::{HighScore, HighScoreStore, HighScores};
+pub use hud::HUD_WIDTH;
+pub use input::{Action, KeyBindings, MenuKey};
+pub use netplay::{Race, RaceRules, Session};
+pub use physics::Velocity;
+pub use player::Player;
+pub use powerup::{PowerUpKind, PowerUps};
+pub use replay::Replay;
+pub use scoring::{ScoreBreakdown, Scoring, ScoringRules};
+pub use settings::GameSettings;
+pub use spatial::RowIndex;
+pub use spawn::SpawnDirector;
+pub use stats::RunStats;
+pub use theme::{ColorDepth, GlyphSet, Paint, Theme, ThemeName};
+pub use weapon::WeaponMode;
+
 /*
 ** GAME PHASES
 * keyboard: binding the keyboard to listening to the keys that inserted
@@ -35,82 +81,185 @@ pub enum GameStatus {
     DEATH, /*PAUSED, FUEL_ENDED*/
 }
 
-#[derive(Clone, Debug)]
+/// @notice a position in the world, in cells with sub-cell precision.
+/// @dev element_i is the row and element_j the column, see physics.rs for the movement helpers.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Location {
-    pub element_i: u16,
-    pub element_j: u16,
+    pub element_i: f32,
+    pub element_j: f32,
 }
-#[derive(Clone, Debug)]
+
+/// @notice the enemy component, the state its behaviour steers with.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Enemy {
-    pub location: Location,
-    pub logo: String,
-}
-#[derive(Clone, Debug)]
-pub struct Bullet {
-    pub location: Location,
-    pub active: bool,
-    pub logo: String,
+    pub kind: EnemyKind,
+    // horizontal heading of the enemy, either 1 or -1.
+    pub direction: f32,
 }
 
-#[derive(Clone, Debug)]
-pub struct Fuel {
-    pub location: Location,
-    pub logo: String,
+/// @notice the river as it was when the last bridge was destroyed.
+/// @dev the player respawns from this snapshot after losing a life.
+#[derive(Clone, Debug, Default)]
+pub struct Checkpoint {
+    pub ground: Vec<(u16, u16)>,
+    pub distance: u32,
+    // the banks after the checkpoint come out the same again.
+    pub river_rng: Option<StdRng>,
 }
 
+pub const FUEL_DEPOT_POINTS: u32 = 80;
+// rows a bullet of the player climbs every tick.
+pub const BULLET_SPEED: f32 = 2.0;
+pub const BRIDGE_BONUS: u32 = 500;
+// a new bridge appears every BRIDGE_INTERVAL rows of travel.
+pub const BRIDGE_INTERVAL: u32 = 300;
+// mixed into the seed for the stream of the banks.
+const RIVER_STREAM: u64 = 0x5249_5645_5221;
+// ticks the river flashes when the boat is hit.
+pub const DAMAGE_FLASH_TICKS: u16 = 3;
+
 pub enum Sound {
     EnemyKilled(String),
     FuelObtained(String),
     BoatCrashed(String),
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct Game2DMatrix {
-    pub player_i: u16,
-    pub player_j: u16,
+    // the boats on the river, one or two in the co-op mode, see player.rs.
+    pub players: Vec<Player>,
     pub max_screen_i: u16,
     pub max_screen_j: u16,
     pub screen_mid: u16,
     pub map: Array2<f64>,
     pub ground: Vec<(u16, u16)>,
-    pub enemies: Vec<Enemy>,
-    pub bullets: Vec<Bullet>,
-    pub fuels: Vec<Fuel>,
+    // every object on the river, the enemies, the bullets, the fuel depots and the bridges.
+    pub world: World,
     pub game_staus: GameStatus,
-    pub score: u32,
-    pub gas: u32,
-    pub enemy_killed: u32,
+    pub bridges_destroyed: u32,
+    pub tick: u32,
+    // rows of river travelled, the difficulty grows with it.
+    pub distance: u32,
+    pub difficulty: Difficulty,
+    // the difficulty parameters at the current distance.
+    pub stage: Stage,
+    pub spawn_director: SpawnDirector,
+    // rows the river scrolls per tick, controlled with Up/Down by any of the players.
+    pub throttle: f32,
+    pub scroll_progress: f32,
+    // whole rows scrolled during the last tick.
+    pub rows_scrolled: u16,
+    pub checkpoint: Option<Checkpoint>,
+    // remaining ticks of the river flashing after a boat was hit.
+    pub damage_flash: u16,
+    // what happened during the current tick.
+    pub events: Vec<GameEvent>,
+    pub achievements: Achievements,
+    // the last achievement unlocked and the ticks it stays on the screen.
+    pub achievement_notice: Option<(&'static Achievement, u16)>,
+    // the statistics of the run so far, see stats.rs.
+    pub stats: RunStats,
+    // broad phase of the bullets against their targets, rebuilt every tick.
+    pub enemy_index: RowIndex,
+    pub settings: GameSettings,
+    // every random choice of the game comes from the seeded rng, the same seed and the same
+    // actions play the same game.
+    pub seed: u64,
+    pub rng: StdRng,
+    // the banks have their own stream of the seed, a seed always sails the same river whatever
+    // happens on it.
+    pub river_rng: StdRng,
+    // the colours and glyphs, chosen in the settings or detected from the terminal.
+    pub theme: Theme,
     pub initialized: bool,
-    pub logo: String,
+    // the games simulated only to be watched, i.e. the other racers of a race, make no sound.
+    pub muted: bool,
+}
+
+impl Default for Game2DMatrix {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Game2DMatrix {
     // NOTE: implementing Defaul trait for Game2DMatrix structure.
     pub fn new() -> Self {
-        // initialize the game information
+        Self::with_settings(GameSettings::default())
+    }
+
+    pub fn with_settings(settings: GameSettings) -> Self {
         let (max_i, max_j) = size().unwrap();
+        Self::with_screen(settings, max_i, max_j)
+    }
+
+    /// @notice a game on a screen of the given size, without looking at the terminal.
+    pub fn with_screen(settings: GameSettings, max_i: u16, max_j: u16) -> Self {
+        // initialize the game information
+        let difficulty = Difficulty::from_preset(settings.difficulty);
+        let theme = Theme::detect(&settings);
+        let seed = settings.seed.unwrap_or_else(rand::random);
+        // the river flows in the middle of the screen right of the HUD panel.
+        let screen_mid = HUD_WIDTH + max_i.saturating_sub(HUD_WIDTH) / 2;
+
+        let players = (0..settings.players as usize)
+            .map(|p| {
+                let (emoji, ascii) = player::logo(p);
+                Player::new(
+                    &settings,
+                    player::boat_column(
+                        (screen_mid, screen_mid),
+                        p,
+                        settings.players as usize,
+                    ),
+                    max_j - 10,
+                    theme.glyph(emoji, ascii).to_string(),
+                )
+            })
+            .collect::<Vec<Player>>();
+        // a game without a life to play is over before it starts.
+        let game_staus = if players.iter().all(Player::is_out) {
+            GameStatus::DEATH
+        } else {
+            GameStatus::ALIVE
+        };
 
         Game2DMatrix {
-            player_i: max_i / 2,
-            player_j: max_j - 10,
+            players,
             max_screen_i: max_i,
             max_screen_j: max_j,
-            screen_mid: max_i / 2,
+            screen_mid,
             map: Array::from_shape_vec(
                 (max_i as usize, max_j as usize),
                 vec![0.0; (max_i * max_j) as usize],
             )
             .unwrap(),
             ground: vec![(0, 0); max_j as usize],
-            enemies: Vec::new(),
-            bullets: Vec::new(),
-            fuels: Vec::new(),
-            game_staus: GameStatus::ALIVE,
-            score: 0,
-            gas: 1500,
-            enemy_killed: 0,
+            world: World::default(),
+            game_staus,
+            bridges_destroyed: 0,
+            tick: 0,
+            distance: 0,
+            stage: difficulty.at(0),
+            difficulty,
+            spawn_director: SpawnDirector::default(),
+            throttle: 1.0,
+            scroll_progress: 0.0,
+            rows_scrolled: 0,
+            checkpoint: None,
+            damage_flash: 0,
+            events: Vec::new(),
+            achievements: Achievements::default(),
+            achievement_notice: None,
+            stats: RunStats::new(chrono::Utc::now().timestamp()),
+            enemy_index: RowIndex::new(max_j as usize),
+            settings,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            river_rng: StdRng::seed_from_u64(seed ^ RIVER_STREAM),
+            theme,
             initialized: false,
-            logo: '⛵'.to_string(),
+            muted: false,
         }
     }
 
@@ -119,17 +268,29 @@ impl Game2DMatrix {
     pub fn initialize_ground(&mut self, screen: &mut Stdout) -> Result<()> {
         // initial phase of screen
         screen.queue(Clear(ClearType::All))?;
+        self.generate_ground();
+        Ok(())
+    }
 
-        let mut rng = rand::thread_rng();
+    /// @notice the banks of the first screen of the river, which is the first checkpoint.
+    /// @dev the right bank stays on the screen, whatever the room left right of the HUD panel.
+    pub fn generate_ground(&mut self) {
+        let rng = &mut self.river_rng;
         // let screen_mid = self.max_screen_i / 2;
         let mut lg_range: u16;
         let mut rg_range: u16;
         let mut change_precision = rng.gen_range(5..10);
+        // the river stays right of the HUD, on a narrow screen the middle of the screen is under it.
+        let river_mid = self.screen_mid.max(HUD_WIDTH + 2);
+        let right_limit = (river_mid + 40)
+            .min(self.max_screen_i.saturating_sub(1))
+            .max(river_mid + 1);
 
         for i in 0..self.map.row(0).len() {
             if i % change_precision == 0 {
-                lg_range = rng.gen_range((self.screen_mid - 40)..self.screen_mid);
-                rg_range = rng.gen_range(self.screen_mid..(self.screen_mid + 40));
+                lg_range =
+                    rng.gen_range(river_mid.saturating_sub(40).max(HUD_WIDTH + 1)..river_mid);
+                rg_range = rng.gen_range(river_mid..right_limit);
 
                 (self.ground[i].0, self.ground[i].1) = (lg_range, rg_range);
                 change_precision = rng.gen_range(change_precision..15);
@@ -138,103 +299,39 @@ impl Game2DMatrix {
             }
         }
 
+        // the start of the river is the first checkpoint.
+        self.checkpoint = Some(Checkpoint {
+            ground: self.ground.clone(),
+            distance: self.distance,
+            river_rng: Some(self.river_rng.clone()),
+        });
+
         self.initialized = true;
-        Ok(())
     }
 
-    pub fn draw(
-        &mut self,
-        screen: &mut Stdout,
-        show_enemy: bool,
-        show_fuel: bool,
-    ) -> Result<&mut Self> {
+    pub fn draw(&mut self, screen: &mut Stdout) -> Result<&mut Self> {
         screen.queue(Clear(ClearType::All))?;
 
         // draw the map as first scence
         for j in 0..(self.map.row(0).len()) {
-            screen
-                .queue(MoveTo(0, j as u16))? // (i, j)
-                .queue(SetForegroundColor(Color::Green))?
-                .queue(SetBackgroundColor(Color::Green))?
-                .queue(Print(" ".repeat(self.ground[j].0 as usize)))?
-                .queue(MoveTo(self.ground[j].0, j as u16))?
-                .queue(SetBackgroundColor(Color::Blue))?
-                .queue(Print(
-                    " ".repeat((self.ground[j].1 - self.ground[j].0) as usize),
-                ))?
-                .queue(MoveTo(self.ground[j].1, j as u16))?
-                .queue(SetBackgroundColor(Color::Green))?
-                .queue(Print(
-                    " ".repeat((self.max_screen_i - self.ground[j].1) as usize),
-                ))?
-                .queue(ResetColor)?;
-        }
-
-        for bullet in self.bullets.iter() {
-            screen
-                .queue(MoveTo(bullet.location.element_j, bullet.location.element_i))?
-                .queue(SetBackgroundColor(Color::Blue))?
-                .queue(Print(&bullet.logo))?;
-        }
-
-        // adjust furl in the posibility of 10% of situations.
-        if show_fuel {
-            self.fuels.push(Fuel {
-                location: Location {
-                    element_i: 2,
-                    element_j: rand::thread_rng().gen_range(self.ground[2].0..self.ground[2].1),
-                },
-                logo: '⛽'.to_string(),
-            })
+            self.draw_river_row(screen, j)?;
         }
 
-        for fuel in self.fuels.iter() {
-            screen
-                .queue(MoveTo(fuel.location.element_j, fuel.location.element_i))?
-                .queue(SetBackgroundColor(Color::Blue))?
-                .queue(Print(&fuel.logo))?;
-        }
+        self.draw_entities(screen)?;
 
-        // adjust enemy in the posibility of 10% of situations
-        if show_enemy {
-            self.enemies.push(Enemy {
-                location: Location {
-                    element_i: 2,
-                    element_j: rand::thread_rng().gen_range(self.ground[2].0..self.ground[2].1),
-                },
-                logo: '👾'.to_string(),
-            })
+        // draw the players, blinking while the respawn invulnerability lasts and gone once wrecked.
+        for player in &self.players {
+            if player.is_sailing() && player.invulnerable % 4 < 2 {
+                screen
+                    .queue(MoveTo(player.i, player.j))?
+                    .queue(self.water_at(player.i, player.j as f32))?
+                    .queue(Print(&player.logo))?
+                    .queue(ResetColor)?;
+            }
         }
 
-        for enemy in self.enemies.iter() {
-            screen
-                .queue(MoveTo(enemy.location.element_j, enemy.location.element_i))?
-                .queue(SetBackgroundColor(Color::Blue))?
-                .queue(Print(&enemy.logo))?
-                .queue(ResetColor)?;
-        }
-
-        // draw the player
-        screen
-            .queue(MoveTo(self.player_i, self.player_j))?
-            .queue(SetBackgroundColor(Color::Blue))?
-            .queue(Print(&self.logo))?;
-
-        // draw the game scores and status
-        let scores_position = (self.max_screen_i / 13, self.max_screen_j / 13);
-
-        screen
-            .queue(SetBackgroundColor(Color::DarkGrey))?
-            .queue(MoveTo(scores_position.0, scores_position.1))?
-            .queue(Print(format!("Score: {}", self.score)))?
-            .queue(SetBackgroundColor(Color::DarkGrey))?
-            .queue(MoveTo(scores_position.0, scores_position.1 + 1))?
-            .queue(Print(format!("Enemy killed: {}", self.enemy_killed)))?
-            .queue(SetBackgroundColor(Color::DarkGrey))?
-            .queue(MoveTo(scores_position.0, scores_position.1 + 2))?
-            .queue(Print(format!("Fuel: {}", self.gas)))?
-            .queue(SetBackgroundColor(Color::DarkGrey))?
-            .queue(ResetColor)?;
+        // the HUD panel is drawn last, over the banks left of the river.
+        self.draw_hud(screen)?;
 
         screen.flush()?;
         Ok(self)
@@ -242,226 +339,402 @@ impl Game2DMatrix {
 
     /// @notice this function perform the elements' movement during the game loop i.e. bullets, enemies, etc.
     /// @dev this function will be called after the draw function to get the modified nd2array game information.
-    pub fn shift_ground_loc(&mut self, change: bool) -> Result<&mut Self> {
-        for i in (1..self.map.row(0).len()).rev() {
-            self.ground[i] = self.ground[i - 1];
+    /// the river scrolls by the throttle every tick, whole rows are scrolled once enough progress is accumulated.
+    pub fn shift_ground_loc(&mut self) -> Result<&mut Self> {
+        self.tick = self.tick.wrapping_add(1);
+        self.events.push(GameEvent::Tick {
+            ms: self.stage.tick_ms,
+        });
+        self.damage_flash = self.damage_flash.saturating_sub(1);
+
+        // nobody is left to sail, the game is over.
+        if self.players.iter().all(Player::is_out) {
+            self.game_staus = GameStatus::DEATH;
+            return Ok(self);
         }
 
-        for bullet in self.bullets.iter_mut().rev() {
-            bullet.location.element_i = bullet.location.element_i.saturating_sub(2);
+        // the river stops while every boat still in the game is wrecked, only the animations go on.
+        let stopped = self
+            .players
+            .iter()
+            .filter(|player| !player.is_out())
+            .all(|player| player.crash.is_some());
+        for p in 0..self.players.len() {
+            if let Some((cause, ticks)) = self.players[p].crash {
+                if ticks <= 1 {
+                    self.players[p].crash = None;
+                    self.lose_life(p, cause);
+                } else {
+                    self.players[p].crash = Some((cause, ticks - 1));
+                }
+            }
+        }
+        if stopped {
+            self.animate_entities();
+            return Ok(self);
         }
-        self.bullets.retain(|bullet| bullet.location.element_i >= 3);
 
-        // manipulate the existing enemies in the map.
-        for enemy in self.enemies.iter_mut() {
-            enemy.location.element_i = enemy.location.element_i.saturating_add(1);
+        self.scroll_progress += self.throttle;
+        self.rows_scrolled = 0;
+        while self.scroll_progress >= 1.0 {
+            self.scroll_progress -= 1.0;
+            self.scroll_row();
+            self.rows_scrolled += 1;
         }
 
-        for fuel in self.fuels.iter_mut() {
-            fuel.location.element_i = fuel.location.element_i.saturating_add(1);
+        // manipulate the existing objects in the map, the behaviours steer and the velocities move.
+        self.steer_enemies();
+        self.move_entities();
+        self.enemy_fire();
+        self.animate_entities();
+
+        let burn = self.throttle / self.stage.fuel_drain_every as f32;
+        for player in self.players.iter_mut().filter(|player| player.is_sailing()) {
+            // the engine burns more fuel the faster the boat goes.
+            let efficiency = if player.power_ups.is_active(PowerUpKind::EfficientEngine) {
+                0.5
+            } else {
+                1.0
+            };
+            player.fuel_burn += efficiency * burn;
+            while player.fuel_burn >= 1.0 {
+                player.fuel_burn -= 1.0;
+                player.gas = player.gas.saturating_sub(1);
+            }
+            player.invulnerable = player.invulnerable.saturating_sub(1);
+            player.power_ups.tick();
+        }
+        self.cool_down_weapons();
+        self.tick_scoring();
+        if let Some((_, ticks)) = self.achievement_notice.as_mut() {
+            *ticks = ticks.saturating_sub(1);
         }
 
-        let mut rng = rand::thread_rng();
-        let delta = rng.gen_range(1..6);
+        Ok(self)
+    }
 
-        self.score += 1;
-        if self.score % 2 == 0 {
-            self.gas -= 1;
+    /// @notice scrolls the river by one row, the objects on the river move down with it.
+    fn scroll_row(&mut self) {
+        for i in (1..self.map.row(0).len()).rev() {
+            self.ground[i] = self.ground[i - 1];
         }
 
-        if change && (self.ground[1].1 < self.max_screen_i - 5) {
-            self.ground[0] = (self.ground[1].0 + delta, self.ground[1].1 + delta);
-        } else if self.ground[1].0 > delta {
-            self.ground[0] = (self.ground[1].0 - delta, self.ground[1].1 - delta);
-        } else {
-            self.ground[0] = self.ground[1];
-        }
+        self.scroll_entities();
 
-        Ok(self)
+        // every row travelled scores, going faster scores faster.
+        self.score_distance();
+        self.events.push(GameEvent::RowTravelled);
+        self.distance += 1;
+        self.stage = self.difficulty.at(self.distance);
+
+        self.ground[0] = next_banks(
+            &mut self.river_rng,
+            self.ground[1],
+            self.stage.river_width,
+            self.max_screen_i,
+        );
+
+        self.spawn();
     }
 
+    /// @notice the banks of the river rows that will scroll in next, the first one is the next row.
+    /// @dev steps a copy of the river generator, so the game is left as it is.
+    pub fn river_ahead(&self, rows: u32) -> Vec<(u16, u16)> {
+        let mut rng = self.river_rng.clone();
+        let mut banks = self.ground[0];
+        (1..=rows)
+            .map(|ahead| {
+                let stage = self.difficulty.at(self.distance + ahead);
+                banks = next_banks(&mut rng, banks, stage.river_width, self.max_screen_i);
+                banks
+            })
+            .collect()
+    }
 
-    pub fn reactions(&mut self /*screen: &mut Stdout*/) -> Result<()> {
-        let user_j: usize = self.player_j as usize;
+    /// @notice speeds the boat up or slows it down by one throttle step, within the settings bounds.
+    pub fn change_throttle(&mut self, steps: i8) {
+        let (min_throttle, max_throttle) = self.settings.throttle_range;
+        self.throttle = (self.throttle + steps as f32 * self.settings.throttle_step)
+            .clamp(min_throttle, max_throttle);
+    }
 
-        if self.gas == 0 {
+    /// @notice takes a life from a player and sends the boat back to the river.
+    /// @dev alone on the river the game goes back to the last checkpoint, in co-op the boat comes
+    /// back where the river is. the game is over once every player lost the last life.
+    pub fn lose_life(&mut self, p: usize, cause: DeathCause) {
+        self.events.push(GameEvent::LifeLost(cause));
+        self.players[p].lives = self.players[p].lives.saturating_sub(1);
+        if self.players.iter().all(Player::is_out) {
             self.game_staus = GameStatus::DEATH;
+            return;
+        }
+        if self.players[p].is_out() {
+            return;
         }
 
-        // handling the boat accidentation with ground
-        if self.player_i <= self.ground[user_j].0 || self.player_i >= self.ground[user_j].1 {
-            self.game_staus = GameStatus::DEATH;
+        if self.players.len() == 1 {
+            if let Some(checkpoint) = self.checkpoint.clone() {
+                self.ground = checkpoint.ground;
+                self.distance = checkpoint.distance;
+                if let Some(river_rng) = checkpoint.river_rng {
+                    self.river_rng = river_rng;
+                }
+                self.stage = self.difficulty.at(self.distance);
+            }
+            self.world.clear();
+            self.throttle = 1.0;
         }
 
-        /////////////////////////////// Take reaction to the enemies chars. ///////////////////////////////
-        let mut enemies_to_remove: Vec<usize> = vec![];
+        let player = &mut self.players[p];
+        player.gas = self.settings.fuel_capacity;
+        player.power_ups.clear();
+        player.fire_cooldown = 0;
+        player.scoring.streak = 0;
+        self.resupply(p, self.settings.ammo_capacity);
+
+        // respawn in the centre of the channel and ignore collisions for a moment.
+        let row = self.max_screen_j - 10;
+        let column = player::boat_column(self.ground[row as usize], p, self.players.len());
+        let player = &mut self.players[p];
+        (player.i, player.j) = (column, row);
+        player.invulnerable = self.settings.invulnerable_ticks;
+    }
 
-        for (idx, enemy) in self.enemies.iter_mut().enumerate() {
-            // player collision with the enemies in the ground.
-            if (enemy.location.element_j - 1..enemy.location.element_j + 1).contains(&self.player_i)
-                && enemy.location.element_i == self.player_j
-            {
-                self.game_staus = GameStatus::DEATH;
-            }
+    /// @notice grants an extra life each time the score of a player passes the next threshold.
+    pub fn award_extra_lives(&mut self) {
+        if self.settings.extra_life_score == 0 {
+            return;
+        }
 
-            // the reaction related to the player's bullets verses the enemies.
-            for bullet in self.bullets.iter_mut() {
-                if bullet.active
-                    && (enemy.location.element_i - 2..enemy.location.element_i + 2)
-                        .contains(&bullet.location.element_i)
-                    && (enemy.location.element_j - 2..enemy.location.element_j + 2)
-                        .contains(&bullet.location.element_j)
-                {
-                    std::thread::spawn(move || {
-                        handle_sound("src/assets/demon-death.wav".to_string(), 1.5);
-                    });
-
-                    enemy.logo = ' '.to_string();
-                    enemies_to_remove.push(idx);
-                    sleep(Duration::from_millis(100));
-                    bullet.active = false;
-                    bullet.logo = ' '.to_string();
+        let mut granted = false;
+        for player in self.players.iter_mut().filter(|player| !player.is_out()) {
+            while player.score >= player.next_extra_life {
+                if player.lives < self.settings.max_lives {
+                    player.lives += 1;
+                    granted = true;
                 }
+                player.next_extra_life += self.settings.extra_life_score;
             }
         }
+        if granted {
+            self.play_sound("src/assets/new-high-score.wav", 1.0);
+        }
+    }
 
-        enemies_to_remove.sort_unstable_by(|a, b| b.cmp(a)); // Sort in reverse order
-        for idx in enemies_to_remove {
-            if idx < self.enemies.len() {
-                self.enemies.remove(idx);
-            }
-            self.enemy_killed += 1;
+    /// @notice plays a sound on its own thread, unless the game is muted.
+    pub fn play_sound(&self, sound_file: &'static str, time_speed: f32) {
+        if !self.muted {
+            std::thread::spawn(move || handle_sound(sound_file.to_string(), time_speed));
+        }
+    }
+
+
+    pub fn reactions(&mut self /*screen: &mut Stdout*/) -> Result<()> {
+        // nothing touches a wreck.
+        if !self.players.iter().any(Player::is_sailing) {
+            self.observe_events();
+            return Ok(());
         }
 
-        /////////////////////////////// Take reaction to the fuel chars. ///////////////////////////////
-        for fuel in self.fuels.iter() {
-            if (fuel.location.element_j - 2..fuel.location.element_j + 2).contains(&self.player_i)
-                && (fuel.location.element_i == self.player_j)
-            {
-                self.gas += 30;
+        /////////////////////////////// Take reaction to the players' bullets. ///////////////////////////////
+        for (entity, shooter) in self.resolve_bullet_hits() {
+            self.destroy(entity, shooter);
+        }
+
+        let mut wrecks = vec![];
+        for p in 0..self.players.len() {
+            if self.players[p].is_sailing() {
+                wrecks.extend(self.boat_reactions(p).map(|cause| (p, cause)));
             }
         }
 
         /////////////////////////////// Take reaction to bottom of the screen ///////////////////////////////
-        self.enemies
-            .retain(|enemy| enemy.location.element_i < self.max_screen_j - 3);
+        self.cleanup_entities();
 
-        self.fuels.retain(|fuel| {
-            !((fuel.location.element_j - 1..fuel.location.element_j + 1).contains(&self.player_i)
-                && (fuel.location.element_i == self.player_j)
-                || (fuel.location.element_i > self.max_screen_j - 3))
-        });
+        self.award_extra_lives();
+
+        for (p, cause) in wrecks {
+            self.wreck_boat(p, cause);
+        }
+
+        self.observe_events();
         Ok(())
     }
 
-    pub fn multi_reactions(&'static mut self) -> Result<&mut Self> {
-        let user_j: usize = self.player_j as usize;
+    // what happens to a boat on the river during a tick, the banks, the fuel and what it touches.
+    // returns what wrecked the boat.
+    fn boat_reactions(&mut self, p: usize) -> Option<DeathCause> {
+        let player = &self.players[p];
+        // what the boat crashed into, the empty tank is checked first since nothing saves from it.
+        let mut crash: Option<DeathCause> = None;
 
-        let arc_game = Arc::new(Mutex::new(self.clone()));
-        let cloned_game = Arc::clone(&arc_game);
+        let boat = player.boat();
 
-        let handle_accidents = thread::spawn(move || {
-            let mut game = cloned_game.lock().unwrap();
+        if player.gas == 0 {
+            crash = Some(DeathCause::OutOfFuel);
+        }
 
-            if game.gas == 0 {
-                game.game_staus = GameStatus::DEATH;
-            }
+        // handling the boat accidentation with ground
+        let (left, right) = self.ground[player.j as usize];
+        if collision::hits_bank(&boat, (left, right)) {
+            crash.get_or_insert(DeathCause::Bank);
+        } else {
+            let clearance = (boat.left - left as f32).min(right as f32 - boat.right());
+            self.events.push(GameEvent::BankClearance(clearance));
+        }
 
-            // handling the boat accidentation with ground
-            if game.player_i <= game.ground[user_j].0 || game.player_i >= game.ground[user_j].1 {
-                game.game_staus = GameStatus::DEATH;
+        /////////////////////////////// Take reaction to what the boat touches. ///////////////////////////////
+        let contacts = self.boat_contacts(boat);
+        // the shield only guards against what floats or flies on the river, not the banks.
+        if let Some(cause) = contacts.crash {
+            if !self.players[p].power_ups.is_active(PowerUpKind::Shield) {
+                crash.get_or_insert(cause);
             }
-        });
+        }
 
-        /////////////////////////////// Take reaction to the enemies with the player ///////////////////////////////
-        let cloned_game2 = Arc::clone(&arc_game);
+        for kind in contacts.power_ups {
+            self.players[p].power_ups.grant(kind);
+            self.events.push(GameEvent::PowerUpCollected(kind));
+            self.play_sound("src/assets/new-high-score.wav", 1.5);
+        }
 
-        let handle_enemy_accident_with_player = std::thread::spawn(move || {
-            let mut game = cloned_game2.lock().unwrap();
-            let (player_i, player_j) = (game.player_i, game.player_j);
+        for _ in 0..contacts.supplies {
+            self.resupply(p, self.settings.ammo_per_supply);
+        }
 
-            let game_status: bool = game.enemies.iter().any(|enemy| {
-                (enemy.location.element_j - 1..enemy.location.element_j + 1).contains(&player_i)
-                    && enemy.location.element_i == player_j
+        let player = &mut self.players[p];
+        if contacts.refuelling {
+            let gas = (player.gas + self.settings.refuel_rate).min(self.settings.fuel_capacity);
+            self.events.push(GameEvent::Refuelled {
+                fuel: gas - player.gas,
             });
+            player.gas = gas;
+        }
 
-            if game_status {
-                game.game_staus = GameStatus::DEATH;
+        // the warning sounds once every time the tank drops below the low level.
+        if player.gas <= self.settings.fuel_low_level {
+            if !player.fuel_low_warned {
+                player.fuel_low_warned = true;
+                self.play_sound("src/assets/power-down-rpg.wav", 1.0);
             }
-        });
-
-        /////////////////////////////// Take reaction to the fuel chars. ///////////////////////////////
-        let cloned_game3 = Arc::clone(&arc_game);
-
-        let handle_fuel_reaction = std::thread::spawn(move || {
-            let mut game = cloned_game3.lock().unwrap();
-            let (player_i, player_j) = (game.player_i, game.player_j);
+        } else {
+            player.fuel_low_warned = false;
+        }
 
-            let res: bool = game.fuels.iter().any(|fuel| {
-                (fuel.location.element_j - 2..fuel.location.element_j + 2).contains(&player_i)
-                    && (fuel.location.element_i == player_j)
-            });
+        // hugging a bank without touching it pays off.
+        if crash.is_none() {
+            self.score_near_miss(p, &boat);
+        }
 
-            if res {
-                game.gas += 40;
-            }
-        });
+        // running out of fuel can not be dodged by the respawn invulnerability.
+        crash.filter(|&cause| cause == DeathCause::OutOfFuel || self.players[p].invulnerable == 0)
+    }
 
-        /////////////////////////////// Take reaction to the enemies with the player ///////////////////////////////
-        let mut enemies_to_remove: Vec<usize> = Vec::new();
-        let mut game_in_main_thread = arc_game.lock().unwrap();
-
-        // todo: add rayon parallelization for this nested loops.
-        for (idx, enemy) in &mut game_in_main_thread.enemies.iter_mut().enumerate() {
-            for bullet in Arc::clone(&arc_game).lock().unwrap().bullets.iter_mut() {
-                if bullet.active
-                    && (enemy.location.element_i - 2..enemy.location.element_i + 2)
-                        .contains(&bullet.location.element_i)
-                    && (enemy.location.element_j - 2..enemy.location.element_j + 2)
-                        .contains(&bullet.location.element_j)
-                {
-                    enemy.logo = ' '.to_string();
-                    enemies_to_remove.push(idx);
-                    sleep(Duration::from_millis(100));
-                    bullet.active = false;
-                    bullet.logo = ' '.to_string();
-                }
-            }
-        }
+    /// @notice hands the events of the tick to the achievements and the statistics.
+    fn observe_events(&mut self) {
 
-        enemies_to_remove.sort_unstable_by(|a, b| b.cmp(a)); // Sort in reverse order
-        for idx in enemies_to_remove {
-            game_in_main_thread.enemies.remove(idx);
-            game_in_main_thread.enemy_killed += 1;
+        let unlocked = self.achievements.observe(&self.events);
+        if let Some(&achievement) = unlocked.last() {
+            self.achievement_notice = Some((achievement, 60));
+            self.play_sound("src/assets/new-high-score.wav", 1.0);
         }
+        self.stats.observe(&self.events);
+        self.events.clear();
+    }
 
-        /////////////////////////////// Take reaction to bottom of the screen ///////////////////////////////
-        let cloned_game4 = Arc::clone(&arc_game);
-
-        let handle_bottom_of_the_game = std::thread::spawn(move || {
-            let mut game = cloned_game4.lock().unwrap();
-            let (player_i, player_j) = (game.player_i, game.player_j);
+    /// @notice a boat crashed, the wreck animation plays before the life is lost.
+    fn wreck_boat(&mut self, p: usize, cause: DeathCause) {
+        let wreck = AnimationKind::Wreck;
+        let player = &mut self.players[p];
+        player.crash = Some((cause, wreck.length()));
+        self.damage_flash = DAMAGE_FLASH_TICKS;
+        self.world.spawn(Components::effect(
+            wreck,
+            Location::new(player.j as f32, player.i as f32),
+        ));
+        self.play_sound("src/assets/power-down-rpg.wav", 0.7);
+    }
 
-            let max_screen_j = game.max_screen_j;
-            game.enemies
-                .retain(|enemy| enemy.location.element_i < max_screen_j - 3);
+    /// @notice rewards the player who shot an entity down and removes it from the river.
+    fn destroy(&mut self, entity: Entity, shooter: usize) {
+        self.score_destroyed(shooter, entity);
 
-            game.fuels.retain(|fuel| {
-                !((fuel.location.element_j - 1..fuel.location.element_j + 1).contains(&player_i)
-                    && (fuel.location.element_i == player_j)
-                    || (fuel.location.element_i > max_screen_j - 3))
-            });
-        });
+        // the bridges blow up in the middle of the river.
+        if let Some(&position) = self.world.position.get(entity) {
+            let column = match self.world.tag.get(entity) {
+                Some(Tag::Bridge) => {
+                    let (left, right) = self.banks_at(position.element_i);
+                    ((left + right) / 2) as f32
+                }
+                _ => position.element_j,
+            };
+            self.world.spawn(Components::effect(
+                AnimationKind::Explosion,
+                Location::new(position.element_i, column),
+            ));
+        }
 
-        handle_accidents.join().unwrap();
-        handle_enemy_accident_with_player.join().unwrap();
-        handle_fuel_reaction.join().unwrap();
-        handle_bottom_of_the_game.join().unwrap();
+        match self.world.tag.get(entity) {
+            Some(Tag::Enemy) => {
+                self.players[shooter].enemy_killed += 1;
+                if let Some(enemy) = self.world.enemy.get(entity) {
+                    self.events.push(GameEvent::EnemyKilled(enemy.kind));
+                }
+                self.play_sound("src/assets/demon-death.wav", 1.5);
+            }
+            Some(Tag::Bridge) => {
+                self.bridges_destroyed += 1;
+                self.events.push(GameEvent::BridgeDestroyed);
+                // a bridge refills the guns of every boat.
+                for p in 0..self.players.len() {
+                    self.resupply(p, self.settings.ammo_per_bridge);
+                }
+                self.checkpoint = Some(Checkpoint {
+                    ground: self.ground.clone(),
+                    distance: self.distance,
+                    river_rng: Some(self.river_rng.clone()),
+                });
+                self.play_sound("src/assets/fire-rpg.wav", 1.0);
+            }
+            Some(Tag::FuelDepot) => self.events.push(GameEvent::DepotShot),
+            _ => {}
+        }
 
-        Ok(self)
+        self.world.despawn(entity);
     }
 }
 
+// the banks of a river row from the banks of the row before it, the river drifts left or right and
+// narrows or widens towards the width of the stage, within the playfield.
+fn next_banks(
+    rng: &mut StdRng,
+    (left, right): (u16, u16),
+    river_width: (u16, u16),
+    max_screen_i: u16,
+) -> (u16, u16) {
+    let delta = rng.gen_range(1..6);
+
+    let (left, right) = if rng.gen_bool(0.5) && right < max_screen_i.saturating_sub(5) {
+        (left + delta, right + delta)
+    } else if left > HUD_WIDTH + delta {
+        (left - delta, right - delta)
+    } else {
+        (left, right)
+    };
+
+    let playfield = max_screen_i.saturating_sub(HUD_WIDTH + 2);
+    let (min_width, max_width) = (river_width.0.min(playfield), river_width.1.min(playfield));
+    let (left, right) =
+        if right - left < min_width && left > HUD_WIDTH + 1 && right < max_screen_i - 1 {
+            (left - 1, right + 1)
+        } else if right - left > max_width {
+            (left + 1, right - 1)
+        } else {
+            (left, right)
+        };
+    (left, right.min(max_screen_i.saturating_sub(1)))
+}
+
 pub fn handle_sound(sound_file: String, time_speed: f32) {
     let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
     let sink = rodio::Sink::try_new(&handle).unwrap();
@@ -472,3 +745,287 @@ pub fn handle_sound(sound_file: String, time_speed: f32) {
     sink.set_speed(time_speed);
     sink.sleep_until_end();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn river_game() -> Game2DMatrix {
+        let mut game = Game2DMatrix::with_screen(GameSettings::default(), 120, 40);
+        game.ground.fill((40, 80));
+        game.players[0].i = 60;
+        game
+    }
+
+    #[test]
+    fn shooting_a_bridge_scores_the_bonus_and_sets_the_checkpoint() {
+        let mut game = river_game();
+        game.world
+            .spawn(Components::bridge(Location::new(20.0, 40.0)));
+        game.world
+            .spawn(Components::player_bullet(Location::new(20.0, 60.0), 0));
+
+        game.reactions().unwrap();
+
+        assert_eq!(game.world.count(Tag::Bridge), 0);
+        assert_eq!(game.bridges_destroyed, 1);
+        assert_eq!(game.players[0].score, BRIDGE_BONUS);
+        assert_eq!(game.checkpoint.unwrap().ground, game.ground);
+        assert_eq!(game.players[0].lives, game.settings.lives);
+    }
+
+    #[test]
+    fn losing_a_life_restarts_from_the_checkpoint() {
+        let mut game = river_game();
+        game.checkpoint = Some(Checkpoint {
+            ground: game.ground.clone(),
+            ..Checkpoint::default()
+        });
+        game.ground.fill((10, 30));
+        game.players[0].gas = 10;
+        game.world.spawn(Components::enemy(
+            EnemyKind::Mine,
+            Location::new(5.0, 20.0),
+            1.0,
+        ));
+
+        game.lose_life(0, DeathCause::Bank);
+
+        assert_eq!(game.players[0].lives, game.settings.lives - 1);
+        assert_eq!(game.game_staus, GameStatus::ALIVE);
+        assert!(game.ground.iter().all(|&banks| banks == (40, 80)));
+        assert!(game.world.is_empty());
+        assert_eq!(game.players[0].gas, game.settings.fuel_capacity);
+        assert_eq!((game.players[0].i, game.players[0].j), (60, 30));
+    }
+
+    #[test]
+    fn extra_lives_come_at_every_multiple_of_the_score_up_to_the_cap() {
+        let settings = GameSettings {
+            lives: 3,
+            max_lives: 5,
+            extra_life_score: 1000,
+            ..GameSettings::default()
+        };
+        let mut game = Game2DMatrix::with_screen(settings, 120, 40);
+        let mut lives_at = |score| {
+            game.players[0].score = score;
+            game.award_extra_lives();
+            game.players[0].lives
+        };
+
+        assert_eq!(lives_at(999), 3);
+        assert_eq!(lives_at(1000), 4);
+        assert_eq!(lives_at(1999), 4);
+        assert_eq!(lives_at(2500), 5);
+        assert_eq!(lives_at(10_000), 5);
+        assert_eq!(game.players[0].next_extra_life, 11_000);
+    }
+
+    #[test]
+    fn collisions_are_ignored_while_invulnerable() {
+        let mut game = river_game();
+        game.checkpoint = Some(Checkpoint {
+            ground: game.ground.clone(),
+            ..Checkpoint::default()
+        });
+        game.lose_life(0, DeathCause::Bank);
+        let lives = game.players[0].lives;
+
+        for _ in 0..game.settings.invulnerable_ticks {
+            // run the boat into the bank every tick.
+            game.players[0].i = game.ground[game.players[0].j as usize].0;
+            game.reactions().unwrap();
+            assert!(game.players[0].crash.is_none());
+            game.shift_ground_loc().unwrap();
+        }
+
+        game.players[0].i = game.ground[game.players[0].j as usize].0;
+        game.reactions().unwrap();
+        assert!(game.players[0].crash.is_some());
+        // the life is lost once the wreck animation is over.
+        while game.players[0].crash.is_some() {
+            game.shift_ground_loc().unwrap();
+        }
+        assert_eq!(game.players[0].lives, lives - 1);
+    }
+
+    #[test]
+    fn the_boat_respawns_in_the_middle_of_the_channel() {
+        let mut game = river_game();
+        let mut ground = game.ground.clone();
+        ground[30] = (20, 50);
+        game.checkpoint = Some(Checkpoint {
+            ground,
+            ..Checkpoint::default()
+        });
+
+        game.lose_life(0, DeathCause::Bank);
+
+        assert_eq!((game.players[0].i, game.players[0].j), (35, 30));
+    }
+
+    fn depot_under_the_boat(game: &mut Game2DMatrix) {
+        game.world.spawn(Components::fuel_depot(Location::new(
+            (game.players[0].j - 1) as f32,
+            game.players[0].i as f32,
+        )));
+    }
+
+    #[test]
+    fn a_depot_refuels_every_tick_up_to_the_capacity() {
+        let mut game = river_game();
+        depot_under_the_boat(&mut game);
+        game.players[0].gas = 1000;
+
+        game.reactions().unwrap();
+        assert_eq!(game.players[0].gas, 1000 + game.settings.refuel_rate);
+        game.reactions().unwrap();
+        assert_eq!(game.players[0].gas, 1000 + 2 * game.settings.refuel_rate);
+        assert_eq!(game.world.count(Tag::FuelDepot), 1);
+
+        game.players[0].gas = game.settings.fuel_capacity - 1;
+        game.reactions().unwrap();
+        assert_eq!(game.players[0].gas, game.settings.fuel_capacity);
+    }
+
+    #[test]
+    fn the_fuel_low_warning_goes_off_once_below_the_low_level() {
+        let mut game = river_game();
+        let low = game.settings.fuel_low_level;
+
+        game.players[0].gas = low + 1;
+        game.reactions().unwrap();
+        assert!(!game.players[0].fuel_low_warned);
+
+        game.players[0].gas = low;
+        game.reactions().unwrap();
+        assert!(game.players[0].fuel_low_warned);
+        game.players[0].gas = low - 1;
+        game.reactions().unwrap();
+        assert!(game.players[0].fuel_low_warned);
+
+        // refuelling above the low level arms the warning again.
+        game.players[0].gas = low + 1;
+        game.reactions().unwrap();
+        assert!(!game.players[0].fuel_low_warned);
+    }
+
+    #[test]
+    fn a_fractional_throttle_builds_up_to_whole_rows() {
+        let mut game = river_game();
+        game.throttle = 0.75;
+
+        let rows: Vec<u16> = (0..4)
+            .map(|_| game.shift_ground_loc().unwrap().rows_scrolled)
+            .collect();
+        assert_eq!(rows, [0, 1, 1, 1]);
+        assert_eq!(game.distance, 3);
+    }
+
+    #[test]
+    fn fuel_and_score_scale_with_the_throttle() {
+        let run = |throttle: f32| {
+            let mut game = river_game();
+            game.throttle = throttle;
+            for _ in 0..10 * game.stage.fuel_drain_every {
+                game.shift_ground_loc().unwrap();
+            }
+            (
+                game.settings.fuel_capacity - game.players[0].gas,
+                game.players[0].score,
+            )
+        };
+
+        let (burnt, score) = run(1.0);
+        assert_eq!(run(2.0), (2 * burnt, 2 * score));
+        assert_eq!(run(0.5), (burnt / 2, score / 2));
+    }
+
+    #[test]
+    fn the_boat_row_stays_fixed_with_the_throttle() {
+        let mut game = river_game();
+        let row = game.players[0].j;
+
+        for steps in [1, 1, 1, -1, -1, -1, -1, -1] {
+            game.change_throttle(steps);
+            game.shift_ground_loc().unwrap();
+            assert_eq!(game.players[0].j, row);
+        }
+        assert_eq!(game.throttle, game.settings.throttle_range.0);
+    }
+
+    #[test]
+    fn enemies_keep_the_fraction_of_a_cell_they_moved() {
+        let mut game = river_game();
+        game.throttle = 0.5;
+        let mine = game.world.spawn(Components {
+            velocity: Velocity::new(0.0, 0.5),
+            ..Components::enemy(EnemyKind::Mine, Location::new(10.0, 60.0), 1.0)
+        });
+
+        game.shift_ground_loc().unwrap();
+        let location = game.world.position.get(mine).unwrap();
+        assert_eq!((location.element_i, location.element_j), (10.0, 60.5));
+        assert_eq!(location.screen_cell(), (60, 10));
+
+        // the river scrolls a row on the second tick.
+        game.shift_ground_loc().unwrap();
+        let location = game.world.position.get(mine).unwrap();
+        assert_eq!((location.element_i, location.element_j), (11.0, 61.0));
+    }
+
+    #[test]
+    fn the_river_ahead_is_the_river_that_scrolls_in() {
+        let settings = GameSettings {
+            seed: Some(11),
+            ..GameSettings::default()
+        };
+        let mut game = Game2DMatrix::with_screen(settings, 120, 40);
+        game.generate_ground();
+        let ahead = game.river_ahead(900);
+        for banks in ahead {
+            game.scroll_row();
+            assert_eq!(game.ground[0], banks);
+        }
+    }
+
+    #[test]
+    fn banks_stay_on_a_narrow_screen() {
+        // below 52 columns the middle of the screen is under the HUD.
+        for width in [40, 50, 80] {
+            for seed in 0..50 {
+                let settings = GameSettings {
+                    seed: Some(seed),
+                    ..GameSettings::default()
+                };
+                let mut game = Game2DMatrix::with_screen(settings, width, 40);
+                game.generate_ground();
+                for _ in 0..400 {
+                    assert!(game.ground.iter().all(|&(left, right)| HUD_WIDTH < left
+                        && left <= right
+                        && right <= game.max_screen_i));
+                    game.scroll_row();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn a_game_without_lives_is_over() {
+        let settings = GameSettings {
+            lives: 0,
+            seed: Some(1),
+            ..GameSettings::default()
+        };
+        let mut game = Game2DMatrix::with_screen(settings, 120, 40);
+        assert!(game.game_staus == GameStatus::DEATH);
+
+        // the last life lost outside of a crash ends the game at the next tick.
+        game.game_staus = GameStatus::ALIVE;
+        game.generate_ground();
+        game.shift_ground_loc().unwrap();
+        assert!(game.game_staus == GameStatus::DEATH);
+        assert_eq!(game.distance, 0);
+    }
+}
@@ -1,30 +1,53 @@
 use crossterm::{
     cursor::{Hide, MoveTo, Show},
-    event::{poll, read, Event, KeyCode},
-    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    event::{poll, read, Event},
+    style::Print,
     terminal::{enable_raw_mode, size, Clear, ClearType},
     ExecutableCommand, QueueableCommand,
 };
 use inline_colorization::*;
 use ndarray::{Array, Array2};
-use rand::prelude::*;
 use river_raid::*;
 use std::cell::RefCell;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use std::{
     borrow::BorrowMut,
-    cell::Ref,
     io::{stdout, Result, Stdout, Write},
-    rc::Rc,
-    thread::{self, sleep},
-    vec,
+    thread::sleep,
 };
 // use shuttle_actix_web::ShuttleActixWeb;
 
 
 mod server;
 
+// blocks until a key is pressed, the keys pressed before are ignored.
+fn wait_for_key() -> Result<()> {
+    while poll(Duration::from_millis(0))? {
+        let _ = read();
+    }
+    while !matches!(read()?, Event::Key(_)) {}
+    Ok(())
+}
+
+// blocks until a key with a meaning in the menus is pressed.
+fn read_menu_key(keys: &KeyBindings) -> Result<MenuKey> {
+    loop {
+        if let Event::Key(event) = read()? {
+            if let Some(key) = keys.menu(event.code) {
+                return Ok(key);
+            }
+        }
+    }
+}
+
+fn date(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// @notice the title screen, `river-raid play [options]` starts here.
 fn main2() -> Result<()> {
     let mut screen = stdout();
     enable_raw_mode().unwrap();
@@ -33,97 +56,464 @@ fn main2() -> Result<()> {
         .execute(crossterm::terminal::SetTitle("River Raid Game"))
         .unwrap();
 
-    let mut nd2array = Game2DMatrix::new();
+    let mut settings = GameSettings::from_args(std::env::args().skip(2))
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
 
-    nd2array.initialize_ground(&mut screen).unwrap();
-    let rc_nd2array2 = Rc::new(nd2array.clone());
+    // the achievements and the high scores carry over between the sessions, the game runs
+    // without them when their storage can not be opened.
+    let store = AchievementStore::open(achievements::ACHIEVEMENTS_DB).ok();
+    let high_scores = HighScoreStore::open(highscores::HIGH_SCORES_DB).ok();
 
+    let mut selection = menu::Selection::new(menu::MenuItem::ALL.len());
+    loop {
+        menu::draw_title(&mut screen, &Theme::detect(&settings), selection.index)?;
 
-    while nd2array.clone().game_staus == GameStatus::ALIVE {
-        // implementing the keyboard binding.
-        if poll(Duration::from_millis(5))? {
-            let key = read().unwrap();
-            while poll(Duration::from_millis(0)).unwrap() {
-                let _ = read();
-            }
+        match read_menu_key(&settings.keys)? {
+            MenuKey::Back => break,
+            MenuKey::Select => match menu::MenuItem::ALL[selection.index] {
+                menu::MenuItem::Play => {
+                    play_game(&mut screen, settings.clone(), false, &store, &high_scores, None)?
+                }
+                menu::MenuItem::DailyChallenge => {
+                    // everyone plays the daily river with the same rules.
+                    let daily = GameSettings {
+                        seed: Some(replay::daily_seed(chrono::Utc::now().date_naive())),
+                        difficulty: DifficultyPreset::default(),
+                        weapon_mode: WeaponMode::default(),
+                        lives: GameSettings::default().lives,
+                        players: 1,
+                        ..settings.clone()
+                    };
+                    play_game(&mut screen, daily, true, &store, &high_scores, None)?
+                }
+                menu::MenuItem::Replays => replays_screen(&mut screen, &settings, &store, &high_scores)?,
+                menu::MenuItem::HighScores => high_scores_screen(&mut screen, &settings, &store, &high_scores)?,
+                menu::MenuItem::Settings => settings_screen(&mut screen, &mut settings)?,
+                menu::MenuItem::Quit => break,
+            },
+            key => selection.step(key),
+        }
+    }
 
-            if let Event::Key(event) = key { 
-                match event.code {
-                    KeyCode::Char('q') => {
-                        break;
-                    }
-                    KeyCode::Right => {
-                        if nd2array.player_i + 1 < nd2array.max_screen_i {
-                            nd2array.player_i += 2;
-                        }
-                    }
-                    KeyCode::Left => {
-                        if nd2array.player_i - 1 > 0 {
-                            nd2array.player_i -= 2;
-                        }
-                    }
-                    KeyCode::Up => {
-                        if nd2array.player_j - 1 > 0 {
-                            nd2array.player_j -= 1;
-                        }
-                    }
-                    KeyCode::Down => {
-                        if nd2array.player_j + 1 < nd2array.max_screen_j {
-                            nd2array.player_j += 1;
-                        }
-                    }
-                    KeyCode::Char(' ') => {
-                        thread::spawn(move || {
-                            handle_sound(
-                                "src/assets/laser_ray_zap_singleshot.wav".to_string(),
-                                1.5,
-                            );
-                        });
-
-                        nd2array.bullets.push(Bullet {
-                            location: Location {
-                                element_i: nd2array.player_j,
-                                element_j: nd2array.player_i,
-                            },
-                            active: true,
-                            logo: '🔥'.to_string(),
-                        });
+    screen.flush().unwrap();
+    screen.execute(Show)?;
+    screen.queue(MoveTo(0, 0))?
+        .queue(Clear(ClearType::All))?
+        .queue(Print(format!("{color_green}Thanks for playing{color_reset}\n")))?;
+    screen.flush()?;
+    crossterm::terminal::disable_raw_mode()
+}
+
+/// @notice runs the game until it is over or the player quits.
+/// @dev the actions come from the keyboard, or from the replay when one is played back. the
+/// recording gets every action with the tick it was applied at and the player who made it. the
+/// ghost, when racing one, sails tick by tick with the game.
+fn run_game(
+    screen: &mut Stdout,
+    nd2array: &mut Game2DMatrix,
+    mut recording: Option<&mut Replay>,
+    playback: Option<&Replay>,
+    mut ghost: Option<&mut Ghost>,
+) -> Result<()> {
+    nd2array.initialize_ground(screen).unwrap();
+    if let Some(ghost) = ghost.as_deref_mut() {
+        ghost.initialize(screen)?;
+    }
+
+    'game: while nd2array.game_staus == GameStatus::ALIVE {
+        let mut actions: Vec<(usize, Action)> = vec![];
+
+        // implementing the keyboard binding, every player gets the first of their keys pressed
+        // since the last tick and the held keys repeating behind it are dropped.
+        let mut waiting = poll(Duration::from_millis(5))?;
+        while waiting {
+            if let Event::Key(event) = read()? {
+                let settings = &nd2array.settings;
+                let bindings = [&settings.keys, &settings.second_keys];
+                let action = bindings
+                    .iter()
+                    .take(nd2array.players.len())
+                    .enumerate()
+                    .find_map(|(p, keys)| Some((p, keys.action(event.code)?)));
+
+                if event.code == settings.keys.quit {
+                    break 'game;
+                } else if let Some((p, action)) = action {
+                    if playback.is_none() && !actions.iter().any(|&(other, _)| other == p) {
+                        actions.push((p, action));
                     }
-                    _ => {}
+                } else if event.code == settings.keys.achievements {
+                    // the game is paused while the achievements are on the screen.
+                    achievements::draw_achievements(screen, &nd2array.achievements, &nd2array.theme)?;
+                    wait_for_key()?;
                 }
             }
+            waiting = poll(Duration::from_millis(0))?;
+        }
+        if let Some(replay) = playback {
+            actions.extend(replay.actions_at(nd2array.tick));
+        }
+
+        for (p, action) in actions {
+            if let Some(recording) = recording.as_deref_mut() {
+                recording.record(nd2array.tick, p, action);
+            }
+            // holding fire only shoots as fast as the gun cools down.
+            if nd2array.apply(p, action) && action == Action::Fire {
+                nd2array.play_sound("src/assets/laser_ray_zap_singleshot.wav", 1.5);
+            }
+        }
+
+        sleep(Duration::from_millis(nd2array.stage.tick_ms));
+
+        nd2array.borrow_mut().draw(screen).unwrap();
+        if let Some(ghost) = ghost.as_deref() {
+            ghost.draw(screen, nd2array)?;
+            screen.flush()?;
         }
 
-        sleep(Duration::from_millis(60));
-        
-        nd2array.borrow_mut()
-        .draw(
-            &mut screen,
-            rand::thread_rng().gen_bool(0.1),
-            rand::thread_rng().gen_bool(0.01),
-        ).unwrap();
-    
-        nd2array
-        .shift_ground_loc(rand::thread_rng().gen_bool(0.5)).unwrap();
+        nd2array.shift_ground_loc().unwrap();
 
         nd2array.reactions().unwrap();
+
+        if let Some(ghost) = ghost.as_deref_mut() {
+            ghost.step()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// @notice a recorded game, once it is over its statistics, replay, high score and achievements are saved.
+/// @dev racing a ghost the game is on the seed, the rules and the screen size of the ghost's replay.
+fn play_game(
+    screen: &mut Stdout,
+    settings: GameSettings,
+    daily: bool,
+    store: &Option<AchievementStore>,
+    high_scores: &Option<HighScoreStore>,
+    ghost: Option<Replay>,
+) -> Result<()> {
+    let mut ghost = ghost.map(|replay| Ghost::new(replay, &settings));
+    let mut nd2array = match ghost.as_ref() {
+        Some(ghost) => Game2DMatrix::with_screen(ghost.settings(&settings), ghost.replay.screen.0, ghost.replay.screen.1),
+        None => Game2DMatrix::with_settings(settings),
+    };
+    if let Some(progress) = store.as_ref().and_then(|store| store.load().ok()) {
+        nd2array.achievements = Achievements::new(progress);
     }
 
+    let mut recording = Replay::new(&nd2array, daily);
+    run_game(screen, &mut nd2array, Some(&mut recording), None, ghost.as_mut())?;
+
+    nd2array.draw_score_breakdown(screen)?;
     handle_sound("src/assets/game_over.wav".to_string(), 1.0);
 
-    screen.flush().unwrap();
-    screen.execute(Show)?;
-    screen.queue(MoveTo(Rc::clone(&rc_nd2array2).max_screen_i / 2, 0))?
-        .queue(Print(format!("{color_green}Thanks for playing{color_reset}\n")))?
-        .queue(Clear(ClearType::All))?;
+    // the breakdown stays until a key is pressed.
+    wait_for_key()?;
+
+    // the statistics are written even when the player quit, the run is still worth tuning with.
+    let stats = nd2array.finish_stats();
+    if let Err(err) = stats::write_summary(std::path::Path::new(&nd2array.settings.stats_dir), &stats) {
+        eprintln!("could not write the run statistics: {err}");
+    }
+    if let Some(history) = nd2array.settings.stats_csv.as_deref() {
+        if let Err(err) = stats::append_history(std::path::Path::new(history), &stats) {
+            eprintln!("could not append the run statistics: {err}");
+        }
+    }
+
+    recording.finish(&nd2array);
+    let replay_path = match recording.save(std::path::Path::new(replay::REPLAYS_DIR)) {
+        Ok(path) => Some(path.display().to_string()),
+        Err(err) => {
+            eprintln!("could not save the replay: {err}");
+            None
+        }
+    };
+
+    // the co-op runs are not ranked against the solo ones.
+    if let Some(high_scores) = high_scores.as_ref().filter(|_| nd2array.players.len() == 1) {
+        let mut table = high_scores.load().unwrap_or_default();
+        let daily_seed = daily.then_some(nd2array.seed);
+        if table.insert(HighScore::new(&nd2array, daily_seed, replay_path)).is_some() {
+            if let Err(err) = high_scores.save(&table) {
+                eprintln!("could not save the high scores: {err}");
+            }
+        }
+    }
+
+    let progress = nd2array.achievements.finish_run();
+    if let Some(store) = store.as_ref() {
+        if let Err(err) = store.save(&progress) {
+            eprintln!("could not save the achievements: {err}");
+        }
+    }
+
+    let token = nd2array
+        .settings
+        .sync_token
+        .clone()
+        .or_else(|| std::env::var("RIVER_RAID_TOKEN").ok());
+    if let (Some(server), Some(token)) = (nd2array.settings.sync_server.as_deref(), token) {
+        if let Err(err) = achievements::sync(server, &token, &progress) {
+            eprintln!("{err}");
+        }
+    }
+
     Ok(())
 }
 
+/// @notice the recorded runs, the selected one is played back or raced as a ghost.
+fn replays_screen(
+    screen: &mut Stdout,
+    settings: &GameSettings,
+    store: &Option<AchievementStore>,
+    high_scores: &Option<HighScoreStore>,
+) -> Result<()> {
+    let replays = Replay::list(std::path::Path::new(replay::REPLAYS_DIR));
+    let lines: Vec<String> = replays
+        .iter()
+        .map(|(_, replay)| {
+            format!(
+                "{}  {:>8} pts  {:>6} rows  {:?}{}{}",
+                date(replay.recorded_at),
+                replay.score,
+                replay.rows_travelled,
+                replay.difficulty,
+                if replay.daily { "  daily" } else { "" },
+                if replay.players > 1 { "  co-op" } else { "" }
+            )
+        })
+        .collect();
+
+    let mut selection = menu::Selection::new(lines.len());
+    loop {
+        let theme = Theme::detect(settings);
+        if lines.is_empty() {
+            menu::draw_list(screen, &theme, "REPLAYS", &["no replays yet".to_string()], None, "back to return")?;
+        } else {
+            menu::draw_list(screen, &theme, "REPLAYS", &lines, Some(selection.index), "select to watch, right to race its ghost, back to return")?;
+        }
+
+        match read_menu_key(&settings.keys)? {
+            MenuKey::Back => return Ok(()),
+            MenuKey::Select if !replays.is_empty() => {
+                let (_, replay) = &replays[selection.index];
+                let mut nd2array = Game2DMatrix::with_screen(replay.settings(settings), replay.screen.0, replay.screen.1);
+                run_game(screen, &mut nd2array, None, Some(replay), None)?;
+                nd2array.draw_score_breakdown(screen)?;
+                wait_for_key()?;
+            }
+            MenuKey::Right if !replays.is_empty() => {
+                let (_, replay) = &replays[selection.index];
+                race_ghost(screen, settings, replay.clone(), store, high_scores)?;
+            }
+            key => selection.step(key),
+        }
+    }
+}
+
+/// @notice the best free runs and the best runs of today's challenge, the best free run with a
+/// replay can be raced as a ghost.
+fn high_scores_screen(
+    screen: &mut Stdout,
+    settings: &GameSettings,
+    store: &Option<AchievementStore>,
+    high_scores: &Option<HighScoreStore>,
+) -> Result<()> {
+    let table = high_scores
+        .as_ref()
+        .and_then(|high_scores| high_scores.load().ok())
+        .unwrap_or_default();
+    let today = replay::daily_seed(chrono::Utc::now().date_naive());
+
+    let mut lines = vec![];
+    for (title, daily) in [("Free runs", None), ("Today's challenge", Some(today))] {
+        lines.push(title.to_string());
+        let entries: Vec<String> = table
+            .table(daily)
+            .enumerate()
+            .map(|(rank, entry)| {
+                format!(
+                    "{:>2}. {:>8} pts  {:>6} rows  {:?}  {}",
+                    rank + 1,
+                    entry.score,
+                    entry.rows_travelled,
+                    entry.difficulty,
+                    date(entry.recorded_at)
+                )
+            })
+            .collect();
+        if entries.is_empty() {
+            lines.push("    no runs yet".to_string());
+        }
+        lines.extend(entries);
+    }
+
+    let best = table
+        .table(None)
+        .find_map(|entry| Replay::load(std::path::Path::new(entry.replay.as_deref()?)).ok());
+    let footer = match best {
+        Some(_) => "select to race the ghost of the best run, back to return",
+        None => "back to return",
+    };
+
+    menu::draw_list(screen, &Theme::detect(settings), "HIGH SCORES", &lines, None, footer)?;
+    loop {
+        match (read_menu_key(&settings.keys)?, best.as_ref()) {
+            (MenuKey::Back, _) => return Ok(()),
+            (MenuKey::Select, Some(best)) => {
+                return race_ghost(screen, settings, best.clone(), store, high_scores)
+            }
+            (MenuKey::Select, None) => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+/// @notice a new run on the river of a replay, with the replay's boat sailing beside as a ghost.
+fn race_ghost(
+    screen: &mut Stdout,
+    settings: &GameSettings,
+    ghost: Replay,
+    store: &Option<AchievementStore>,
+    high_scores: &Option<HighScoreStore>,
+) -> Result<()> {
+    // a ghost of today's challenge makes the run one of today's challenge too.
+    let daily = ghost.daily && ghost.seed == replay::daily_seed(chrono::Utc::now().date_naive());
+    play_game(screen, settings.clone(), daily, store, high_scores, Some(ghost))
+}
+
+/// @notice changes the settings of the session, left and right go through the values.
+fn settings_screen(screen: &mut Stdout, settings: &mut GameSettings) -> Result<()> {
+    let mut selection = menu::Selection::new(menu::SettingField::ALL.len());
+    loop {
+        let lines: Vec<String> = menu::SettingField::ALL
+            .iter()
+            .map(|field| format!("{:<12} {}", field.label(), field.value(settings)))
+            .collect();
+        menu::draw_list(screen, &Theme::detect(settings), "SETTINGS", &lines, Some(selection.index), "left/right to change, back to return")?;
+
+        let field = menu::SettingField::ALL[selection.index];
+        match read_menu_key(&settings.keys)? {
+            MenuKey::Back => return Ok(()),
+            MenuKey::Left => field.cycle(settings, -1),
+            MenuKey::Right | MenuKey::Select => field.cycle(settings, 1),
+            key => selection.step(key),
+        }
+    }
+}
+
+
 
+/// @notice the achievements screen outside of a game, `river-raid achievements [--theme t] [--glyphs g]`.
+fn show_achievements() -> Result<()> {
+    let settings = GameSettings::from_args(std::env::args().skip(2))
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+    let theme = Theme::detect(&settings);
+    let store = AchievementStore::open(achievements::ACHIEVEMENTS_DB)
+        .map_err(std::io::Error::other)?;
+    let progress = store
+        .load()
+        .map_err(std::io::Error::other)?;
+
+    let mut screen = stdout();
+    enable_raw_mode()?;
+    screen.execute(Hide)?;
+    achievements::draw_achievements(&mut screen, &Achievements::new(progress), &theme)?;
+    wait_for_key()?;
+
+    screen.queue(Clear(ClearType::All))?.execute(Show)?;
+    crossterm::terminal::disable_raw_mode()
+}
+
+/// @notice a race over the network, `host` waits for the other racers and `join` races on a host.
+/// @dev the race ends once every racer is out or left, the local run is not ranked nor recorded.
+fn race(hosting: bool) -> Result<()> {
+    let invalid = |err: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, err);
+    let mut args = std::env::args().skip(2);
+    let address = if hosting {
+        None
+    } else {
+        Some(args.next().ok_or_else(|| invalid("missing the address of the host".to_string()))?)
+    };
+    let settings = GameSettings::from_args(args).map_err(invalid)?;
+
+    let session = match address {
+        None => {
+            let listener = std::net::TcpListener::bind(("0.0.0.0", settings.port))?;
+            println!(
+                "waiting for {} more racers on port {}...",
+                settings.racers - 1,
+                settings.port
+            );
+            let seed = settings.seed.unwrap_or_else(rand::random);
+            Session::host(listener, RaceRules::new(&settings, seed, size()?))?
+        }
+        Some(address) => {
+            println!("joining the race on {address}...");
+            Session::join(address)?
+        }
+    };
+
+    let mut screen = stdout();
+    enable_raw_mode()?;
+    screen.execute(Hide)?;
+    let mut race = Race::new(session, &settings);
+    race.initialize(&mut screen)?;
+
+    let mut actions: Vec<Action> = vec![];
+    // once the local boat is out the race goes on with its ghosts until the others are out too.
+    'race: while !race.is_over() {
+        // the first key pressed since the last tick is sent, like in the local games.
+        while poll(Duration::from_millis(0))? {
+            if let Event::Key(event) = read()? {
+                if event.code == settings.keys.quit {
+                    race.session.quit();
+                    break 'race;
+                } else if let Some(action) = settings.keys.action(event.code) {
+                    if actions.is_empty() {
+                        actions.push(action);
+                    }
+                }
+            }
+        }
+
+        // the racers wait for each other, a tick is only simulated with the frames of everyone.
+        if race.step(&mut actions)? {
+            if race.fired {
+                race.local()
+                    .play_sound("src/assets/laser_ray_zap_singleshot.wav", 1.5);
+            }
+            race.draw(&mut screen)?;
+            sleep(Duration::from_millis(race.local().stage.tick_ms));
+        } else {
+            sleep(Duration::from_millis(1));
+        }
+    }
+
+    race.games[race.session.me].draw_score_breakdown(&mut screen)?;
+    handle_sound("src/assets/game_over.wav".to_string(), 1.0);
+    wait_for_key()?;
+
+    screen.queue(Clear(ClearType::All))?.execute(Show)?;
+    crossterm::terminal::disable_raw_mode()
+}
 
 // todo this function should applied at a separate cargo
 #[actix_web::main]
 async fn main() -> Result<()>{
+    // `river-raid play [--difficulty easy|normal|hard] [--lives n]` starts the game.
+    match std::env::args().nth(1).as_deref() {
+        Some("play") => return main2(),
+        Some("achievements") => return show_achievements(),
+        // `river-raid host [--racers n] [--port p]` and `river-raid join <host:port>` race over the network.
+        Some("host") => return race(true),
+        Some("join") => return race(false),
+        _ => {}
+    }
+
     println!("Running web server...");
     server::run_server().await
 }
\ No newline at end of file
@@ -0,0 +1,299 @@
+use crate::input::MenuKey;
+use crate::theme::{ColorDepth, GlyphSet, Paint, Theme, ThemeName};
+use crate::{DifficultyPreset, GameSettings, WeaponMode};
+use crossterm::{
+    cursor::MoveTo,
+    style::{Print, ResetColor},
+    terminal::{size, Clear, ClearType},
+    QueueableCommand,
+};
+use std::io::{Result, Stdout, Write};
+
+const LOGO: [&str; 6] = [
+    r" ____  _                  ____       _     _ ",
+    r"|  _ \(_)_   _____ _ __  |  _ \ __ _(_) __| |",
+    r"| |_) | \ \ / / _ \ '__| | |_) / _` | |/ _` |",
+    r"|  _ <| |\ V /  __/ |    |  _ < (_| | | (_| |",
+    r"|_| \_\_| \_/ \___|_|    |_| \_\__,_|_|\__,_|",
+    r"",
+];
+
+/// @notice the entries of the main menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuItem {
+    Play,
+    DailyChallenge,
+    Replays,
+    HighScores,
+    Settings,
+    Quit,
+}
+
+impl MenuItem {
+    pub const ALL: [MenuItem; 6] = [
+        MenuItem::Play,
+        MenuItem::DailyChallenge,
+        MenuItem::Replays,
+        MenuItem::HighScores,
+        MenuItem::Settings,
+        MenuItem::Quit,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MenuItem::Play => "Play",
+            MenuItem::DailyChallenge => "Daily Challenge",
+            MenuItem::Replays => "Replays",
+            MenuItem::HighScores => "High Scores",
+            MenuItem::Settings => "Settings",
+            MenuItem::Quit => "Quit",
+        }
+    }
+}
+
+/// @notice the selected line of a list, moved up and down with the menu keys and wrapping around.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Selection {
+    pub index: usize,
+    pub len: usize,
+}
+
+impl Selection {
+    pub fn new(len: usize) -> Self {
+        Selection { index: 0, len }
+    }
+
+    pub fn step(&mut self, key: MenuKey) {
+        if self.len == 0 {
+            return;
+        }
+        match key {
+            MenuKey::Up => self.index = (self.index + self.len - 1) % self.len,
+            MenuKey::Down => self.index = (self.index + 1) % self.len,
+            _ => {}
+        }
+    }
+}
+
+/// @notice the title screen, the logo above the main menu.
+pub fn draw_title(screen: &mut Stdout, theme: &Theme, selected: usize) -> Result<()> {
+    let (columns, rows) = size()?;
+    let logo_width = LOGO.iter().map(|line| line.len()).max().unwrap_or(0) as u16;
+    let top = rows.saturating_sub(LOGO.len() as u16 + 2 * MenuItem::ALL.len() as u16) / 2;
+
+    screen.queue(Clear(ClearType::All))?;
+    for (k, line) in LOGO.iter().enumerate() {
+        screen
+            .queue(MoveTo(
+                columns.saturating_sub(logo_width) / 2,
+                top + k as u16,
+            ))?
+            .queue(theme.paint(Paint::Unlocked))?
+            .queue(Print(line))?
+            .queue(ResetColor)?;
+    }
+
+    let lines: Vec<String> = MenuItem::ALL
+        .iter()
+        .map(|item| item.label().to_string())
+        .collect();
+    draw_lines(
+        screen,
+        theme,
+        top + LOGO.len() as u16 + 1,
+        &lines,
+        Some(selected),
+    )?;
+    screen.flush()
+}
+
+/// @notice a screen with a title and a list of lines, the selected one highlighted.
+pub fn draw_list(
+    screen: &mut Stdout,
+    theme: &Theme,
+    title: &str,
+    lines: &[String],
+    selected: Option<usize>,
+    footer: &str,
+) -> Result<()> {
+    let (columns, rows) = size()?;
+
+    screen
+        .queue(Clear(ClearType::All))?
+        .queue(MoveTo(columns.saturating_sub(title.len() as u16) / 2, 1))?
+        .queue(theme.paint(Paint::Unlocked))?
+        .queue(Print(title))?
+        .queue(ResetColor)?;
+
+    // the list scrolls to keep the selected line on the screen.
+    let visible = rows.saturating_sub(6) as usize;
+    let first = selected.map_or(0, |k| k.saturating_sub(visible.saturating_sub(1)));
+    let shown: Vec<String> = lines.iter().skip(first).take(visible).cloned().collect();
+    draw_lines(screen, theme, 3, &shown, selected.map(|k| k - first))?;
+
+    screen
+        .queue(MoveTo(
+            columns.saturating_sub(footer.len() as u16) / 2,
+            rows - 2,
+        ))?
+        .queue(theme.paint(Paint::Locked))?
+        .queue(Print(footer))?
+        .queue(ResetColor)?;
+    screen.flush()
+}
+
+// the lines centred under each other from the top row, the selected one with a marker.
+fn draw_lines(
+    screen: &mut Stdout,
+    theme: &Theme,
+    top: u16,
+    lines: &[String],
+    selected: Option<usize>,
+) -> Result<()> {
+    let (columns, _) = size()?;
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0) as u16
+        + 4;
+    let left = columns.saturating_sub(width) / 2;
+
+    for (k, line) in lines.iter().enumerate() {
+        let chosen = selected == Some(k);
+        screen
+            .queue(MoveTo(left, top + 2 * k as u16))?
+            .queue(theme.paint(if chosen { Paint::Notice } else { Paint::Hud }))?
+            .queue(Print(format!(
+                "{} {:<width$} ",
+                if chosen { ">" } else { " " },
+                line,
+                width = width as usize - 3
+            )))?
+            .queue(ResetColor)?;
+    }
+    Ok(())
+}
+
+/// @notice the settings that can be changed from the menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettingField {
+    Players,
+    Difficulty,
+    Weapon,
+    Lives,
+    Theme,
+    Glyphs,
+    Colors,
+}
+
+impl SettingField {
+    pub const ALL: [SettingField; 7] = [
+        SettingField::Players,
+        SettingField::Difficulty,
+        SettingField::Weapon,
+        SettingField::Lives,
+        SettingField::Theme,
+        SettingField::Glyphs,
+        SettingField::Colors,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SettingField::Players => "Players",
+            SettingField::Difficulty => "Difficulty",
+            SettingField::Weapon => "Weapon",
+            SettingField::Lives => "Lives",
+            SettingField::Theme => "Theme",
+            SettingField::Glyphs => "Glyphs",
+            SettingField::Colors => "Colours",
+        }
+    }
+
+    /// @notice the current value, the detected ones read as auto.
+    pub fn value(self, settings: &GameSettings) -> String {
+        let auto = |value: Option<String>| value.unwrap_or_else(|| "auto".to_string());
+        match self {
+            SettingField::Players => match settings.players {
+                1 => "1".to_string(),
+                players => format!("{players} co-op"),
+            },
+            SettingField::Difficulty => format!("{:?}", settings.difficulty),
+            SettingField::Weapon => format!("{:?}", settings.weapon_mode),
+            SettingField::Lives => settings.lives.to_string(),
+            SettingField::Theme => auto(settings.theme.map(|theme| format!("{theme:?}"))),
+            SettingField::Glyphs => auto(settings.glyphs.map(|glyphs| format!("{glyphs:?}"))),
+            SettingField::Colors => auto(settings.colors.map(|depth| format!("{depth:?}"))),
+        }
+    }
+
+    /// @notice moves the setting to the next (step 1) or the previous (step -1) value.
+    pub fn cycle(self, settings: &mut GameSettings, step: isize) {
+        match self {
+            SettingField::Players => settings.players = cycle(&[1, 2], settings.players, step),
+            SettingField::Difficulty => {
+                settings.difficulty = cycle(
+                    &[
+                        DifficultyPreset::Easy,
+                        DifficultyPreset::Normal,
+                        DifficultyPreset::Hard,
+                    ],
+                    settings.difficulty,
+                    step,
+                )
+            }
+            SettingField::Weapon => {
+                settings.weapon_mode = cycle(
+                    &[WeaponMode::Unlimited, WeaponMode::Ammo, WeaponMode::Heat],
+                    settings.weapon_mode,
+                    step,
+                )
+            }
+            SettingField::Lives => {
+                settings.lives =
+                    (settings.lives as isize + step).clamp(1, settings.max_lives as isize) as u8
+            }
+            SettingField::Theme => {
+                settings.theme = cycle(
+                    &[
+                        None,
+                        Some(ThemeName::Classic),
+                        Some(ThemeName::HighContrast),
+                        Some(ThemeName::ColourblindSafe),
+                        Some(ThemeName::Monochrome),
+                    ],
+                    settings.theme,
+                    step,
+                )
+            }
+            SettingField::Glyphs => {
+                settings.glyphs = cycle(
+                    &[None, Some(GlyphSet::Emoji), Some(GlyphSet::Ascii)],
+                    settings.glyphs,
+                    step,
+                )
+            }
+            SettingField::Colors => {
+                settings.colors = cycle(
+                    &[
+                        None,
+                        Some(ColorDepth::Ansi16),
+                        Some(ColorDepth::Ansi256),
+                        Some(ColorDepth::TrueColor),
+                    ],
+                    settings.colors,
+                    step,
+                )
+            }
+        }
+    }
+}
+
+// the value `step` places away from the current one, wrapping around.
+fn cycle<T: Copy + PartialEq>(values: &[T], current: T, step: isize) -> T {
+    let at = values
+        .iter()
+        .position(|&value| value == current)
+        .unwrap_or(0) as isize;
+    values[(at + step).rem_euclid(values.len() as isize) as usize]
+}
@@ -0,0 +1,493 @@
+use crate::input::Action;
+use crate::theme::Paint;
+use crate::{DifficultyPreset, Game2DMatrix, GameSettings, GameStatus, WeaponMode};
+use crossterm::{
+    cursor::MoveTo,
+    style::{Print, ResetColor},
+    QueueableCommand,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Stdout, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+pub const DEFAULT_PORT: u16 = 7777;
+pub const MAX_RACERS: u8 = 8;
+// the frame of a tick is sent this many ticks ahead, the time it has to reach the other racers
+// before they wait for it.
+pub const INPUT_DELAY: u32 = 3;
+
+/// @notice the rules of a race, the host sends them to every racer so they all sail the same river.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RaceRules {
+    pub seed: u64,
+    // the river is generated for the size of the host's screen.
+    pub screen: (u16, u16),
+    pub difficulty: DifficultyPreset,
+    pub weapon: WeaponMode,
+    pub lives: u8,
+    pub racers: usize,
+}
+
+impl RaceRules {
+    pub fn new(settings: &GameSettings, seed: u64, screen: (u16, u16)) -> Self {
+        RaceRules {
+            seed,
+            screen,
+            difficulty: settings.difficulty,
+            weapon: settings.weapon_mode,
+            lives: settings.lives,
+            racers: settings.racers as usize,
+        }
+    }
+
+    /// @notice the settings of every racer's game, the rest of them come from `base`.
+    pub fn settings(&self, base: &GameSettings) -> GameSettings {
+        GameSettings {
+            seed: Some(self.seed),
+            difficulty: self.difficulty,
+            weapon_mode: self.weapon,
+            lives: self.lives,
+            players: 1,
+            ..base.clone()
+        }
+    }
+}
+
+/// @notice what the racers send each other, one json message per line.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Message {
+    // from the host to a racer once everyone joined, with the number of the racer.
+    Start {
+        rules: RaceRules,
+        you: usize,
+    },
+    // the actions of a racer at a tick.
+    Frame {
+        player: usize,
+        tick: u32,
+        actions: Vec<Action>,
+    },
+    // the racer left the race.
+    Quit {
+        player: usize,
+    },
+}
+
+fn send(stream: &mut TcpStream, message: &Message) -> io::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    stream.write_all(&line)
+}
+
+// reads the messages of a link on its own thread, a closed or broken link ends with None.
+fn listen(stream: TcpStream, link: usize, inbox: Sender<(usize, Option<Message>)>) {
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines() {
+            let Ok(message) = line.and_then(|line| Ok(serde_json::from_str(&line)?)) else {
+                break;
+            };
+            if inbox.send((link, Some(message))).is_err() {
+                return;
+            }
+        }
+        let _ = inbox.send((link, None));
+    });
+}
+
+/// @notice the connection of a racer to the others and the input frames received from them.
+/// @dev the racers are connected through the host, which relays the messages of every racer to
+/// the others. racer 0 is the host, the others are numbered in the order they joined.
+pub struct Session {
+    pub me: usize,
+    pub rules: RaceRules,
+    host: bool,
+    // the host has a link to every other racer, the others a single link to the host.
+    links: Vec<TcpStream>,
+    inbox: Receiver<(usize, Option<Message>)>,
+    // the frames of every racer not applied yet, by tick.
+    frames: Vec<BTreeMap<u32, Vec<Action>>>,
+    // the racers who left the race, their frames stop coming.
+    pub gone: Vec<bool>,
+}
+
+impl Session {
+    fn new(
+        me: usize,
+        rules: RaceRules,
+        host: bool,
+        links: Vec<TcpStream>,
+        inbox: Receiver<(usize, Option<Message>)>,
+    ) -> Self {
+        Session {
+            me,
+            host,
+            links,
+            inbox,
+            frames: vec![BTreeMap::new(); rules.racers],
+            gone: vec![false; rules.racers],
+            rules,
+        }
+    }
+
+    /// @notice waits for the other racers to join, then starts the race.
+    pub fn host(listener: TcpListener, rules: RaceRules) -> io::Result<Session> {
+        let (sender, inbox) = mpsc::channel();
+        let mut links = Vec::new();
+        for link in 0..rules.racers - 1 {
+            let (stream, _) = listener.accept()?;
+            stream.set_nodelay(true)?;
+            listen(stream.try_clone()?, link, sender.clone());
+            links.push(stream);
+        }
+
+        for (link, stream) in links.iter_mut().enumerate() {
+            let start = Message::Start {
+                rules: rules.clone(),
+                you: link + 1,
+            };
+            send(stream, &start)?;
+        }
+        Ok(Session::new(0, rules, true, links, inbox))
+    }
+
+    /// @notice joins the race of a host, returns once the host started it.
+    pub fn join<A: ToSocketAddrs>(address: A) -> io::Result<Session> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+        let (sender, inbox) = mpsc::channel();
+        listen(stream.try_clone()?, 0, sender);
+
+        match inbox.recv() {
+            Ok((_, Some(Message::Start { rules, you }))) if you < rules.racers => {
+                Ok(Session::new(you, rules, false, vec![stream], inbox))
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "the host did not start the race",
+            )),
+        }
+    }
+
+    /// @notice takes the messages received since the last poll, the host passes them on.
+    pub fn poll(&mut self) {
+        while let Ok((link, message)) = self.inbox.try_recv() {
+            let Some(message) = message else {
+                self.disconnected(link);
+                continue;
+            };
+            // a racer only speaks for their own boat, the host drops whatever else they send.
+            if self.host {
+                match message {
+                    Message::Frame { player, .. } | Message::Quit { player }
+                        if player == link + 1 => {}
+                    _ => continue,
+                }
+            }
+            self.relay(link, &message);
+            match message {
+                Message::Frame {
+                    player,
+                    tick,
+                    actions,
+                } if player < self.frames.len() => {
+                    self.frames[player].insert(tick, actions);
+                }
+                Message::Quit { player } if player < self.gone.len() => self.gone[player] = true,
+                _ => {}
+            }
+        }
+    }
+
+    /// @notice sends the local actions of a tick to the other racers.
+    pub fn send_frame(&mut self, tick: u32, actions: Vec<Action>) {
+        let frame = Message::Frame {
+            player: self.me,
+            tick,
+            actions,
+        };
+        // a broken link is noticed by its listener, the racers behind it leave the race then.
+        for stream in &mut self.links {
+            let _ = send(stream, &frame);
+        }
+        if let Message::Frame { actions, .. } = frame {
+            self.frames[self.me].insert(tick, actions);
+        }
+    }
+
+    pub fn frame(&self, player: usize, tick: u32) -> Option<&Vec<Action>> {
+        self.frames[player].get(&tick)
+    }
+
+    /// @notice tells the other racers the local one left the race.
+    pub fn quit(&mut self) {
+        let quit = Message::Quit { player: self.me };
+        for stream in &mut self.links {
+            let _ = send(stream, &quit);
+        }
+    }
+
+    // the host passes the messages of a racer on to the others.
+    fn relay(&mut self, from: usize, message: &Message) {
+        if !self.host {
+            return;
+        }
+        for (link, stream) in self.links.iter_mut().enumerate() {
+            if link != from {
+                let _ = send(stream, message);
+            }
+        }
+    }
+
+    // the racer behind a closed link left, for a racer that is everyone but them.
+    fn disconnected(&mut self, link: usize) {
+        if self.host {
+            let player = link + 1;
+            if !self.gone[player] {
+                self.gone[player] = true;
+                self.relay(link, &Message::Quit { player });
+            }
+        } else {
+            for player in (0..self.gone.len()).filter(|&player| player != self.me) {
+                self.gone[player] = true;
+            }
+        }
+    }
+}
+
+/// @notice a race on the same river, the game of every racer is simulated on every machine.
+/// @dev the games only depend on the seed and the actions, so the racers exchange nothing but their
+/// input frames. the simulation is in lockstep, a tick is simulated once the frame of every racer
+/// still racing arrived, and the other racers are drawn as ghosts on the local river.
+pub struct Race {
+    pub session: Session,
+    pub games: Vec<Game2DMatrix>,
+    pub tick: u32,
+    // the local boat fired during the last tick.
+    pub fired: bool,
+}
+
+impl Race {
+    pub fn new(mut session: Session, base: &GameSettings) -> Self {
+        let settings = session.rules.settings(base);
+        let (max_i, max_j) = session.rules.screen;
+        let games = (0..session.rules.racers)
+            .map(|p| {
+                let mut game = Game2DMatrix::with_screen(settings.clone(), max_i, max_j);
+                game.muted = p != session.me;
+                game
+            })
+            .collect();
+
+        // nobody acts before the first frames sent during the race arrive.
+        for tick in 0..INPUT_DELAY {
+            session.send_frame(tick, vec![]);
+        }
+        Race {
+            session,
+            games,
+            tick: 0,
+            fired: false,
+        }
+    }
+
+    pub fn initialize(&mut self, screen: &mut Stdout) -> io::Result<()> {
+        for game in &mut self.games {
+            game.initialize_ground(screen)?;
+        }
+        Ok(())
+    }
+
+    pub fn local(&self) -> &Game2DMatrix {
+        &self.games[self.session.me]
+    }
+
+    /// @notice every game of the race is over.
+    pub fn is_over(&self) -> bool {
+        self.games
+            .iter()
+            .all(|game| game.game_staus != GameStatus::ALIVE)
+    }
+
+    /// @notice simulates the next tick once every racer still racing sent their frame of it.
+    /// @dev the local actions are sent for INPUT_DELAY ticks later and `actions` is emptied. a racer
+    /// who left is out of the race at the first tick without a frame. returns whether the tick was
+    /// simulated.
+    pub fn step(&mut self, actions: &mut Vec<Action>) -> io::Result<bool> {
+        self.session.poll();
+        let tick = self.tick;
+        let waiting = self.games.iter().enumerate().any(|(p, game)| {
+            game.game_staus == GameStatus::ALIVE
+                && !self.session.gone[p]
+                && self.session.frame(p, tick).is_none()
+        });
+        if waiting {
+            return Ok(false);
+        }
+
+        let me = self.session.me;
+        if self.games[me].game_staus == GameStatus::ALIVE {
+            self.session
+                .send_frame(tick + INPUT_DELAY, std::mem::take(actions));
+        }
+
+        self.fired = false;
+        for (p, game) in self.games.iter_mut().enumerate() {
+            if game.game_staus != GameStatus::ALIVE {
+                continue;
+            }
+            let Some(frame) = self.session.frames[p].remove(&tick) else {
+                game.game_staus = GameStatus::DEATH;
+                continue;
+            };
+            for action in frame {
+                if game.apply(0, action) && action == Action::Fire && p == me {
+                    self.fired = true;
+                }
+            }
+            game.shift_ground_loc()?;
+            game.reactions()?;
+        }
+
+        self.tick += 1;
+        Ok(true)
+    }
+
+    /// @notice the racers by the rows they travelled, the furthest first.
+    pub fn standings(&self) -> Vec<usize> {
+        let mut racers: Vec<usize> = (0..self.games.len()).collect();
+        racers.sort_by_key(|&p| std::cmp::Reverse(self.games[p].stats.rows_travelled));
+        racers
+    }
+
+    /// @notice draws the local game with the other racers as ghosts and the standings of the race.
+    pub fn draw(&mut self, screen: &mut Stdout) -> io::Result<()> {
+        let me = self.session.me;
+        self.games[me].draw(screen)?;
+
+        let local = &self.games[me];
+        for (p, game) in self.games.iter().enumerate() {
+            if p == me || game.game_staus != GameStatus::ALIVE {
+                continue;
+            }
+            let label = char::from_digit(p as u32 + 1, 10).unwrap_or('?');
+            local.draw_ghost(screen, &game.players[0], game.distance, label)?;
+        }
+
+        // the standings at the top right of the river.
+        for (place, p) in self.standings().into_iter().enumerate() {
+            let game = &self.games[p];
+            let line = format!(
+                "{}. P{}{} {:>6} rows{}",
+                place + 1,
+                p + 1,
+                if p == me { " (you)" } else { "" },
+                game.stats.rows_travelled,
+                if game.game_staus == GameStatus::ALIVE {
+                    ""
+                } else {
+                    " out"
+                },
+            );
+            let column = local
+                .max_screen_i
+                .saturating_sub(line.chars().count() as u16 + 1);
+            screen
+                .queue(MoveTo(column, 1 + place as u16))?
+                .queue(local.theme.paint(Paint::Hud))?
+                .queue(Print(line))?
+                .queue(ResetColor)?;
+        }
+
+        screen.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn racers_on_localhost_simulate_the_same_games() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let settings = GameSettings::default();
+        let rules = RaceRules::new(&settings, 42, (120, 40));
+
+        let racer = thread::spawn(move || Session::join(address).unwrap());
+        let host = Session::host(listener, rules.clone()).unwrap();
+        let racer = racer.join().unwrap();
+        assert_eq!(racer.rules, rules);
+        assert_eq!(racer.me, 1);
+
+        let mut races = [Race::new(host, &settings), Race::new(racer, &settings)];
+        // a straight channel instead of initialize_ground, which clears the terminal.
+        for game in races.iter_mut().flat_map(|race| race.games.iter_mut()) {
+            game.ground.fill((50, 100));
+            game.initialized = true;
+        }
+
+        // both racers steer their own way, lockstep keeps every copy of their games in sync.
+        let scripts = [
+            [Action::Left, Action::Fire, Action::Faster],
+            [Action::Right, Action::Fire, Action::Slower],
+        ];
+        let mut pending = [vec![], vec![]];
+        while races.iter().any(|race| race.tick < 60) {
+            for (p, race) in races.iter_mut().enumerate() {
+                if pending[p].is_empty() {
+                    pending[p].push(scripts[p][race.tick as usize % 3]);
+                }
+                if race.tick < 60 && !race.step(&mut pending[p]).unwrap() {
+                    thread::sleep(std::time::Duration::from_millis(1));
+                }
+            }
+        }
+
+        for p in 0..2 {
+            let (host, racer) = (&races[0].games[p], &races[1].games[p]);
+            assert_eq!(host.distance, racer.distance);
+            assert_eq!(host.score(), racer.score());
+            assert_eq!(host.players[0].i, racer.players[0].i);
+            assert_eq!(host.ground, racer.ground);
+        }
+        assert_ne!(
+            races[0].games[0].players[0].i,
+            races[0].games[1].players[0].i
+        );
+    }
+
+    #[test]
+    fn the_host_drops_frames_sent_for_another_racer() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let rules = RaceRules::new(&GameSettings::default(), 42, (120, 40));
+
+        let racer = thread::spawn(move || {
+            let mut stream = TcpStream::connect(address).unwrap();
+            let mut start = String::new();
+            BufReader::new(stream.try_clone().unwrap())
+                .read_line(&mut start)
+                .unwrap();
+            for player in [0, 1] {
+                let frame = Message::Frame {
+                    player,
+                    tick: 0,
+                    actions: vec![Action::Left],
+                };
+                send(&mut stream, &frame).unwrap();
+            }
+            stream
+        });
+        let mut host = Session::host(listener, rules).unwrap();
+        let _stream = racer.join().unwrap();
+
+        while host.frame(1, 0).is_none() {
+            host.poll();
+            thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(host.frame(0, 0), None);
+    }
+}
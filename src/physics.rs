@@ -0,0 +1,59 @@
+use crate::Location;
+
+/// @notice a velocity in cells per tick, di along the rows and dj along the columns.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Velocity {
+    pub di: f32,
+    pub dj: f32,
+}
+
+impl Velocity {
+    pub fn new(di: f32, dj: f32) -> Self {
+        Velocity { di, dj }
+    }
+}
+
+impl Location {
+    pub fn new(element_i: f32, element_j: f32) -> Self {
+        Location {
+            element_i,
+            element_j,
+        }
+    }
+
+    /// @notice moves the location by the velocity of one tick.
+    pub fn integrate(&mut self, velocity: Velocity) {
+        self.element_i += velocity.di;
+        self.element_j += velocity.dj;
+    }
+
+    /// @notice the terminal cell of the location as (column, row), the order MoveTo expects.
+    /// @dev world positions are only quantised here, right before rendering.
+    pub fn screen_cell(&self) -> (u16, u16) {
+        (
+            self.element_j.max(0.0).floor() as u16,
+            self.element_i.max(0.0).floor() as u16,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_keep_the_fraction_of_a_cell_until_rendered() {
+        let mut location = Location::new(10.0, 5.0);
+        let velocity = Velocity::new(0.25, 0.5);
+
+        location.integrate(velocity);
+        assert_eq!((location.element_i, location.element_j), (10.25, 5.5));
+        assert_eq!(location.screen_cell(), (5, 10));
+
+        for _ in 0..3 {
+            location.integrate(velocity);
+        }
+        assert_eq!((location.element_i, location.element_j), (11.0, 7.0));
+        assert_eq!(location.screen_cell(), (7, 11));
+    }
+}
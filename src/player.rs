@@ -0,0 +1,89 @@
+use crate::collision::{self, Aabb};
+use crate::{DeathCause, GameSettings, PowerUps, Scoring};
+
+// columns between the boats of a co-op game.
+pub const BOAT_SPACING: u16 = 6;
+
+/// @notice a boat on the river and everything that belongs to its pilot.
+/// @dev a game has one player, or two sharing the river in the co-op mode. the river, its
+/// speed and everything on it are shared, the rest is kept per player.
+#[derive(Clone, Debug)]
+pub struct Player {
+    // the column and the row of the boat.
+    pub i: u16,
+    pub j: u16,
+    pub logo: String,
+    pub score: u32,
+    // the kill streak and where the score came from.
+    pub scoring: Scoring,
+    pub gas: u32,
+    pub fuel_burn: f32,
+    pub fuel_low_warned: bool,
+    pub enemy_killed: u32,
+    pub lives: u8,
+    // remaining ticks of the respawn invulnerability.
+    pub invulnerable: u16,
+    // the boat is wrecked, the life is lost when the ticks of the wreck animation run out.
+    pub crash: Option<(DeathCause, u16)>,
+    pub next_extra_life: u32,
+    pub power_ups: PowerUps,
+    // ticks left before the gun can fire again.
+    pub fire_cooldown: u16,
+    // the state of the gun in the ammo and heat modes, see weapon.rs.
+    pub ammo: u32,
+    pub heat: f32,
+    pub overheated: bool,
+}
+
+impl Player {
+    pub fn new(settings: &GameSettings, i: u16, j: u16, logo: String) -> Self {
+        Player {
+            i,
+            j,
+            logo,
+            score: 0,
+            scoring: Scoring::default(),
+            gas: settings.fuel_capacity,
+            fuel_burn: 0.0,
+            fuel_low_warned: false,
+            enemy_killed: 0,
+            lives: settings.lives,
+            invulnerable: 0,
+            crash: None,
+            next_extra_life: settings.extra_life_score,
+            power_ups: PowerUps::default(),
+            fire_cooldown: 0,
+            ammo: settings.ammo_capacity,
+            heat: 0.0,
+            overheated: false,
+        }
+    }
+
+    /// @notice the player lost the last life, the boat is gone from the river.
+    pub fn is_out(&self) -> bool {
+        self.lives == 0
+    }
+
+    /// @notice the boat is on the river and can be steered, neither wrecked nor out of the game.
+    pub fn is_sailing(&self) -> bool {
+        !self.is_out() && self.crash.is_none()
+    }
+
+    pub fn boat(&self) -> Aabb {
+        collision::boat_box(self.i, self.j)
+    }
+}
+
+/// @notice the emoji and the ascii boat of every player.
+pub fn logo(player: usize) -> (&'static str, &'static str) {
+    match player {
+        0 => ("⛵", "/\\"),
+        _ => ("🚤", "^^"),
+    }
+}
+
+/// @notice the column of a boat on a channel, the boats of a co-op game side by side around its middle.
+pub fn boat_column(banks: (u16, u16), player: usize, players: usize) -> u16 {
+    let middle = (banks.0 + banks.1) / 2;
+    (middle + player as u16 * BOAT_SPACING).saturating_sub((players as u16 - 1) * BOAT_SPACING / 2)
+}
@@ -0,0 +1,118 @@
+use rand::Rng;
+
+/// @notice the power-ups the boat can collect on the river.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerUpKind {
+    // three bullets fanning out per shot.
+    SpreadShot,
+    // a shorter fire cooldown.
+    RapidFire,
+    // the bullets go through what they destroy.
+    Piercing,
+    // the enemies, their bullets and the bridges can not harm the boat.
+    Shield,
+    // the engine burns half the fuel.
+    EfficientEngine,
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 5] = [
+        PowerUpKind::SpreadShot,
+        PowerUpKind::RapidFire,
+        PowerUpKind::Piercing,
+        PowerUpKind::Shield,
+        PowerUpKind::EfficientEngine,
+    ];
+
+    pub fn glyph(self) -> char {
+        match self {
+            PowerUpKind::SpreadShot => '🔱',
+            PowerUpKind::RapidFire => '⚡',
+            PowerUpKind::Piercing => '🎯',
+            PowerUpKind::Shield => '🛡',
+            PowerUpKind::EfficientEngine => '🔋',
+        }
+    }
+
+    /// @notice the glyph in the ascii sprite set, two characters like the emoji.
+    pub fn ascii(self) -> &'static str {
+        match self {
+            PowerUpKind::SpreadShot => "Sp",
+            PowerUpKind::RapidFire => "Rf",
+            PowerUpKind::Piercing => "Pc",
+            PowerUpKind::Shield => "Sh",
+            PowerUpKind::EfficientEngine => "En",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PowerUpKind::SpreadShot => "Spread shot",
+            PowerUpKind::RapidFire => "Rapid fire",
+            PowerUpKind::Piercing => "Piercing shot",
+            PowerUpKind::Shield => "Shield",
+            PowerUpKind::EfficientEngine => "Efficient engine",
+        }
+    }
+
+    /// @notice ticks the power-up lasts once collected.
+    pub fn duration(self) -> u16 {
+        match self {
+            PowerUpKind::SpreadShot => 300,
+            PowerUpKind::RapidFire => 250,
+            PowerUpKind::Piercing => 200,
+            PowerUpKind::Shield => 150,
+            PowerUpKind::EfficientEngine => 400,
+        }
+    }
+
+    pub fn pick<R: Rng>(rng: &mut R) -> PowerUpKind {
+        PowerUpKind::ALL[rng.gen_range(0..PowerUpKind::ALL.len())]
+    }
+
+    fn slot(self) -> usize {
+        PowerUpKind::ALL
+            .iter()
+            .position(|&kind| kind == self)
+            .expect("every kind is in ALL")
+    }
+}
+
+/// @notice the remaining ticks of every power-up of the boat.
+/// @dev collecting a power-up that is still active restarts its timer.
+#[derive(Clone, Debug, Default)]
+pub struct PowerUps {
+    remaining: [u16; PowerUpKind::ALL.len()],
+}
+
+impl PowerUps {
+    pub fn grant(&mut self, kind: PowerUpKind) {
+        self.remaining[kind.slot()] = kind.duration();
+    }
+
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.remaining(kind) > 0
+    }
+
+    pub fn remaining(&self, kind: PowerUpKind) -> u16 {
+        self.remaining[kind.slot()]
+    }
+
+    /// @notice the active power-ups with their remaining ticks.
+    pub fn active(&self) -> impl Iterator<Item = (PowerUpKind, u16)> + '_ {
+        PowerUpKind::ALL
+            .iter()
+            .map(|&kind| (kind, self.remaining(kind)))
+            .filter(|&(_, ticks)| ticks > 0)
+    }
+
+    pub fn tick(&mut self) {
+        for ticks in self.remaining.iter_mut() {
+            *ticks = ticks.saturating_sub(1);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.remaining = [0; PowerUpKind::ALL.len()];
+    }
+}
@@ -0,0 +1,178 @@
+use crate::input::Action;
+use crate::{DifficultyPreset, Game2DMatrix, GameSettings, WeaponMode};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// where the replays of the finished runs are kept.
+pub const REPLAYS_DIR: &str = "replays";
+
+/// @notice a run that can be played again, the seed and the actions of the player are enough
+/// since the simulation only depends on them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    // the river is generated for the size of the screen.
+    pub screen: (u16, u16),
+    pub difficulty: DifficultyPreset,
+    pub weapon: WeaponMode,
+    pub lives: u8,
+    // two players share the river in the co-op runs.
+    #[serde(default = "one_player")]
+    pub players: u8,
+    // a run of the daily challenge.
+    pub daily: bool,
+    // unix timestamp of the end of the run.
+    pub recorded_at: i64,
+    pub score: u32,
+    pub rows_travelled: u32,
+    // the actions of the players with the tick they were applied at and the player, in order.
+    pub inputs: Vec<(u32, usize, Action)>,
+}
+
+impl Replay {
+    /// @notice an empty recording of the game.
+    pub fn new(game: &Game2DMatrix, daily: bool) -> Self {
+        Replay {
+            seed: game.seed,
+            screen: (game.max_screen_i, game.max_screen_j),
+            difficulty: game.settings.difficulty,
+            weapon: game.settings.weapon_mode,
+            lives: game.settings.lives,
+            players: game.settings.players,
+            daily,
+            recorded_at: 0,
+            score: 0,
+            rows_travelled: 0,
+            inputs: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, tick: u32, player: usize, action: Action) {
+        self.inputs.push((tick, player, action));
+    }
+
+    /// @notice the actions applied at a tick, with the player who made them.
+    pub fn actions_at(&self, tick: u32) -> impl Iterator<Item = (usize, Action)> + '_ {
+        let first = self.inputs.partition_point(|&(t, _, _)| t < tick);
+        self.inputs[first..]
+            .iter()
+            .take_while(move |&&(t, _, _)| t == tick)
+            .map(|&(_, player, action)| (player, action))
+    }
+
+    /// @notice the settings to play the replay with, the rest of them come from `base`.
+    pub fn settings(&self, base: &GameSettings) -> GameSettings {
+        GameSettings {
+            seed: Some(self.seed),
+            difficulty: self.difficulty,
+            weapon_mode: self.weapon,
+            lives: self.lives,
+            players: self.players,
+            ..base.clone()
+        }
+    }
+
+    /// @notice closes the recording once the run is over.
+    pub fn finish(&mut self, game: &Game2DMatrix) {
+        self.recorded_at = chrono::Utc::now().timestamp();
+        self.score = game.score();
+        self.rows_travelled = game.stats.rows_travelled;
+    }
+
+    /// @notice writes the replay as `<recorded_at>-<seed>.json` in the directory, returns its path.
+    pub fn save(&self, dir: &Path) -> io::Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}-{}.json", self.recorded_at, self.seed));
+        fs::write(&path, serde_json::to_string(self)?)?;
+        Ok(path)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Replay> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// @notice the replays of the directory, the newest first, the unreadable ones are skipped.
+    pub fn list(dir: &Path) -> Vec<(PathBuf, Replay)> {
+        let Ok(files) = fs::read_dir(dir) else {
+            return vec![];
+        };
+
+        let mut replays: Vec<(PathBuf, Replay)> = files
+            .filter_map(|file| file.ok().map(|file| file.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| Some((path.clone(), Replay::load(&path).ok()?)))
+            .collect();
+        replays.sort_by_key(|(_, replay)| std::cmp::Reverse(replay.recorded_at));
+        replays
+    }
+}
+
+fn one_player() -> u8 {
+    1
+}
+
+/// @notice the seed of the daily challenge, everyone gets the same river on the same day.
+pub fn daily_seed(date: NaiveDate) -> u64 {
+    date.year() as u64 * 10_000 + date.month() as u64 * 100 + date.day() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay(inputs: Vec<(u32, usize, Action)>) -> Replay {
+        Replay {
+            seed: 1,
+            screen: (120, 40),
+            difficulty: DifficultyPreset::Normal,
+            weapon: WeaponMode::Unlimited,
+            lives: 3,
+            players: 2,
+            daily: false,
+            recorded_at: 0,
+            score: 0,
+            rows_travelled: 0,
+            inputs,
+        }
+    }
+
+    #[test]
+    fn actions_are_found_by_their_tick() {
+        let replay = replay(vec![
+            (0, 0, Action::Fire),
+            (3, 0, Action::Left),
+            (3, 1, Action::Right),
+            (7, 1, Action::Faster),
+        ]);
+
+        let at = |tick| replay.actions_at(tick).collect::<Vec<_>>();
+        assert_eq!(at(0), [(0, Action::Fire)]);
+        assert_eq!(at(1), []);
+        assert_eq!(at(3), [(0, Action::Left), (1, Action::Right)]);
+        assert_eq!(at(7), [(1, Action::Faster)]);
+        assert_eq!(at(8), []);
+    }
+
+    #[test]
+    fn recorded_actions_play_back_at_their_tick() {
+        let mut recording = replay(vec![]);
+        recording.record(2, 0, Action::Slower);
+        recording.record(2, 0, Action::Fire);
+        recording.record(5, 1, Action::Left);
+
+        assert_eq!(
+            recording.actions_at(2).collect::<Vec<_>>(),
+            [(0, Action::Slower), (0, Action::Fire)]
+        );
+        assert_eq!(recording.actions_at(5).count(), 1);
+    }
+
+    #[test]
+    fn the_daily_seed_is_the_date() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        assert_eq!(daily_seed(date), 20261019);
+    }
+}
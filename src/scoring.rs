@@ -0,0 +1,281 @@
+use crate::collision::Aabb;
+use crate::ecs::{Entity, Tag};
+use crate::theme::Paint;
+use crate::Game2DMatrix;
+use crossterm::{
+    cursor::MoveTo,
+    style::{Print, ResetColor},
+    terminal::{Clear, ClearType},
+    QueueableCommand,
+};
+use std::io::{Result, Stdout, Write};
+
+/// @notice the tunable rules of the scoring.
+#[derive(Clone, Debug)]
+pub struct ScoringRules {
+    // points for every row travelled.
+    pub distance_points: u32,
+    // shooting a depot while the fuel is low costs these points instead of earning its value.
+    pub fuel_depot_penalty: u32,
+    // kills at most streak_window ticks apart build a streak, without kills it loses a kill per window.
+    pub streak_window: u16,
+    // every kill of a streak raises the multiplier by streak_step, up to max_multiplier.
+    pub streak_step: f32,
+    pub max_multiplier: f32,
+    // passing within near_miss_columns of a bank without touching it earns near_miss_points,
+    // at most once every near_miss_cooldown ticks.
+    pub near_miss_columns: u16,
+    pub near_miss_points: u32,
+    pub near_miss_cooldown: u16,
+}
+
+impl Default for ScoringRules {
+    fn default() -> Self {
+        ScoringRules {
+            distance_points: 1,
+            fuel_depot_penalty: 150,
+            streak_window: 60,
+            streak_step: 0.25,
+            max_multiplier: 4.0,
+            near_miss_columns: 2,
+            near_miss_points: 5,
+            near_miss_cooldown: 10,
+        }
+    }
+}
+
+/// @notice where the points of a game came from.
+/// @dev the kill points include the streak multiplier, streak_bonus tells how much of them it added.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ScoreBreakdown {
+    pub distance: u32,
+    pub enemies: u32,
+    pub bridges: u32,
+    // negative when more depots were shot on a low tank than with fuel to spare.
+    pub fuel_depots: i64,
+    pub near_misses: u32,
+    pub streak_bonus: u32,
+    pub best_streak: u32,
+}
+
+impl ScoreBreakdown {
+    pub fn total(&self) -> u32 {
+        let total = (self.distance + self.enemies + self.bridges + self.near_misses) as i64
+            + self.fuel_depots;
+        total.max(0) as u32
+    }
+}
+
+/// @notice the kill streak and the points earned so far.
+#[derive(Clone, Debug, Default)]
+pub struct Scoring {
+    pub breakdown: ScoreBreakdown,
+    // kills in the current streak.
+    pub streak: u32,
+    // ticks left before the streak loses a kill.
+    streak_timer: u16,
+    near_miss_timer: u16,
+}
+
+impl Scoring {
+    pub fn multiplier(&self, rules: &ScoringRules) -> f32 {
+        let bonus_kills = self.streak.saturating_sub(1) as f32;
+        (1.0 + bonus_kills * rules.streak_step).min(rules.max_multiplier)
+    }
+}
+
+impl Game2DMatrix {
+    /// @notice the points for a row of travel, for every boat on the river.
+    pub fn score_distance(&mut self) {
+        let points = self.settings.scoring.distance_points;
+        for player in self.players.iter_mut().filter(|player| player.is_sailing()) {
+            player.scoring.breakdown.distance += points;
+            player.score += points;
+        }
+    }
+
+    /// @notice the points of a player for shooting down an entity, kills of enemies and bridges build the streak.
+    pub fn score_destroyed(&mut self, p: usize, entity: Entity) {
+        let rules = &self.settings.scoring;
+        let value = self.world.score_value.get(entity).copied().unwrap_or(0);
+        let player = &mut self.players[p];
+
+        match self.world.tag.get(entity) {
+            Some(Tag::Enemy | Tag::Bridge) => {
+                player.scoring.streak += 1;
+                player.scoring.streak_timer = rules.streak_window;
+                player.scoring.breakdown.best_streak = player
+                    .scoring
+                    .breakdown
+                    .best_streak
+                    .max(player.scoring.streak);
+
+                let points = (value as f32 * player.scoring.multiplier(rules)).round() as u32;
+                player.scoring.breakdown.streak_bonus += points - value;
+                if self.world.tag.get(entity) == Some(&Tag::Enemy) {
+                    player.scoring.breakdown.enemies += points;
+                } else {
+                    player.scoring.breakdown.bridges += points;
+                }
+                player.score += points;
+            }
+            Some(Tag::FuelDepot) if player.gas <= self.settings.fuel_low_level => {
+                let penalty = rules.fuel_depot_penalty;
+                player.scoring.breakdown.fuel_depots -= penalty as i64;
+                player.score = player.score.saturating_sub(penalty);
+            }
+            _ => {
+                player.scoring.breakdown.fuel_depots += value as i64;
+                player.score += value;
+            }
+        }
+    }
+
+    /// @notice the points for a boat passing close to a bank, called when it did not crash.
+    pub fn score_near_miss(&mut self, p: usize, boat: &Aabb) {
+        let rules = &self.settings.scoring;
+        let player = &mut self.players[p];
+        if player.scoring.near_miss_timer > 0 {
+            return;
+        }
+
+        let (left, right) = self.ground[player.j as usize];
+        let margin = rules.near_miss_columns as f32;
+        let near_bank = boat.left - left as f32 <= margin || right as f32 - boat.right() <= margin;
+        if near_bank {
+            let points = rules.near_miss_points;
+            player.scoring.near_miss_timer = rules.near_miss_cooldown;
+            player.scoring.breakdown.near_misses += points;
+            player.score += points;
+        }
+    }
+
+    /// @notice lets the streaks decay and the near miss timers run for a tick.
+    pub fn tick_scoring(&mut self) {
+        for player in &mut self.players {
+            let scoring = &mut player.scoring;
+            scoring.near_miss_timer = scoring.near_miss_timer.saturating_sub(1);
+
+            if scoring.streak == 0 {
+                continue;
+            }
+            scoring.streak_timer = scoring.streak_timer.saturating_sub(1);
+            if scoring.streak_timer == 0 {
+                scoring.streak -= 1;
+                scoring.streak_timer = self.settings.scoring.streak_window;
+            }
+        }
+    }
+
+    /// @notice the score of the game, every player's points together.
+    pub fn score(&self) -> u32 {
+        self.players.iter().map(|player| player.score).sum()
+    }
+
+    pub fn enemy_killed(&self) -> u32 {
+        self.players.iter().map(|player| player.enemy_killed).sum()
+    }
+
+    /// @notice the game over screen, where the points of every player came from.
+    pub fn draw_score_breakdown(&self, screen: &mut Stdout) -> Result<()> {
+        // a column of numbers per player.
+        let row = |label: &str, value: &dyn Fn(&ScoreBreakdown) -> String| {
+            let values: String = self
+                .players
+                .iter()
+                .map(|player| format!("{:>8}", value(&player.scoring.breakdown)))
+                .collect();
+            format!("{label:<14} {values}")
+        };
+        let totals: String = self
+            .players
+            .iter()
+            .map(|player| format!("{:>8}", player.score))
+            .collect();
+        let header: String = (1..=self.players.len())
+            .map(|p| format!("{:>8}", format!("P{p}")))
+            .collect();
+
+        let mut lines = vec!["GAME OVER".to_string(), String::new()];
+        if self.players.len() > 1 {
+            lines.push(format!("{:<14} {header}", ""));
+        }
+        lines.extend([
+            row("Distance:", &|b| b.distance.to_string()),
+            row("Enemies:", &|b| b.enemies.to_string()),
+            row("Bridges:", &|b| b.bridges.to_string()),
+            row("Fuel depots:", &|b| b.fuel_depots.to_string()),
+            row("Near misses:", &|b| b.near_misses.to_string()),
+            row("  streak bonus", &|b| b.streak_bonus.to_string()),
+            row("  best streak", &|b| b.best_streak.to_string()),
+            String::new(),
+            format!("{:<14} {totals}", "Total:"),
+            String::new(),
+            "press any key".to_string(),
+        ]);
+
+        let width = 15 + 8 * self.players.len();
+        let top = self.max_screen_j.saturating_sub(lines.len() as u16) / 2;
+        let left = self.max_screen_i.saturating_sub(width as u16) / 2;
+
+        screen.queue(Clear(ClearType::All))?;
+        for (k, line) in lines.iter().enumerate() {
+            screen
+                .queue(MoveTo(left, top + k as u16))?
+                .queue(self.theme.paint(Paint::Hud))?
+                .queue(Print(format!("{line:<width$}")))?
+                .queue(ResetColor)?;
+        }
+        screen.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::Components;
+    use crate::{EnemyKind, GameSettings, Location};
+
+    fn streak(kills: u32) -> Scoring {
+        Scoring {
+            streak: kills,
+            ..Scoring::default()
+        }
+    }
+
+    #[test]
+    fn the_multiplier_grows_with_the_streak_up_to_the_cap() {
+        let rules = ScoringRules::default();
+        assert_eq!(streak(0).multiplier(&rules), 1.0);
+        assert_eq!(streak(1).multiplier(&rules), 1.0);
+        assert_eq!(streak(2).multiplier(&rules), 1.25);
+        assert_eq!(streak(5).multiplier(&rules), 2.0);
+        assert_eq!(streak(100).multiplier(&rules), rules.max_multiplier);
+    }
+
+    #[test]
+    fn kills_in_a_row_score_the_combo_and_the_streak_decays() {
+        let mut game = Game2DMatrix::with_screen(GameSettings::default(), 120, 40);
+        let kill = |game: &mut Game2DMatrix| {
+            let enemy = Components::enemy(EnemyKind::Ship, Location::new(10.0, 60.0), 1.0);
+            let value = enemy.score_value.unwrap();
+            let entity = game.world.spawn(enemy);
+            let before = game.players[0].score;
+            game.score_destroyed(0, entity);
+            (value, game.players[0].score - before)
+        };
+
+        let (value, first) = kill(&mut game);
+        assert_eq!(first, value);
+        let (value, second) = kill(&mut game);
+        assert_eq!(second, (value as f32 * 1.25).round() as u32);
+        assert_eq!(game.players[0].scoring.streak, 2);
+        assert_eq!(game.players[0].scoring.breakdown.best_streak, 2);
+
+        // a window without kills costs a kill of the streak.
+        for _ in 0..game.settings.scoring.streak_window {
+            game.tick_scoring();
+        }
+        assert_eq!(game.players[0].scoring.streak, 1);
+    }
+}
@@ -1,4 +1,4 @@
-use actix_web::{get, post, web, App, HttpRequest, HttpResponse, HttpServer, Responder, ResponseError};
+use actix_web::{get, post, web, App, HttpRequest, HttpResponse, HttpServer, Responder};
 use sled::Db;
 use actix_files as fs;
 use serde::{Serialize, Deserialize};
@@ -16,7 +16,7 @@ extern crate serde_json;
 
 use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
 use argon2::password_hash::{SaltString, rand_core::OsRng};
-use jwt::{encode, Header, EncodingKey};
+use jwt::{decode, encode, DecodingKey, Header, EncodingKey, Validation};
 use chrono::{Utc, Duration};
 use std::env;
 
@@ -50,6 +50,23 @@ fn verify_password(hash: &str, password: &str) -> bool {
     argon2.verify_password(password.as_bytes(), &parsed_hash).is_ok()
 }
 
+fn jwt_secret() -> String {
+    env::var("JWT_SECRET").unwrap_or_else(|_| "secret".to_string())
+}
+
+// the username of a valid `Authorization: Bearer <token>` header.
+fn authorized_user(req: &HttpRequest) -> Option<String> {
+    let header = req.headers().get("Authorization")?.to_str().ok()?;
+    let token = header.strip_prefix("Bearer ")?;
+    let claims = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(jwt_secret().as_ref()),
+        &Validation::default(),
+    )
+    .ok()?;
+    Some(claims.claims.sub)
+}
+
 fn generate_jwt(username: &str) -> String {
     let expiration = Utc::now()
         .checked_add_signed(Duration::days(1))
@@ -61,7 +78,7 @@ fn generate_jwt(username: &str) -> String {
         exp: expiration as usize,
     };
 
-    let secret = env::var("JWT_SECRET").unwrap_or_else(|_| "secret".to_string());
+    let secret = jwt_secret();
     encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_ref())).unwrap()
 }   
 
@@ -101,6 +118,36 @@ async fn get_user(db: web::Data<Mutex<Db>>) -> impl Responder {
     HttpResponse::Ok().body("test")
 }
 
+// the achievements progress of the player, synced by the game at the end of every run.
+#[post("/achievements")]
+async fn upload_achievements(
+    db: web::Data<Mutex<Db>>,
+    req: HttpRequest,
+    progress: web::Json<serde_json::Value>,
+) -> impl Responder {
+    let Some(username) = authorized_user(&req) else {
+        return HttpResponse::Unauthorized().body("invalid token");
+    };
+
+    let db = db.lock().unwrap();
+    let serialized = serde_json::to_vec(&progress.into_inner()).unwrap();
+    db.insert(format!("achievements/{username}").as_bytes(), serialized).unwrap();
+    HttpResponse::Ok().body("achievements synced")
+}
+
+#[get("/achievements")]
+async fn download_achievements(db: web::Data<Mutex<Db>>, req: HttpRequest) -> impl Responder {
+    let Some(username) = authorized_user(&req) else {
+        return HttpResponse::Unauthorized().body("invalid token");
+    };
+
+    let db = db.lock().unwrap();
+    match db.get(format!("achievements/{username}")).unwrap() {
+        Some(serialized) => HttpResponse::Ok().content_type("application/json").body(serialized.to_vec()),
+        None => HttpResponse::NotFound().body("no achievements yet"),
+    }
+}
+
 #[get("/signup")]
 async fn signup_form(tmpl: web::Data<Tera>) -> impl Responder {
     let s = tmpl.render("signup.html", &Context::new()).unwrap();
@@ -138,6 +185,8 @@ pub async fn run_server() -> std::io::Result<()> {
             .route("/add_item", web::post().to(add_item))
             .service(signup_form)
             .service(home_page)
+            .service(upload_achievements)
+            .service(download_achievements)
             // .service(get_user)
     })
     .workers(WORKERS as usize)
@@ -0,0 +1,190 @@
+use crate::{
+    ColorDepth, DifficultyPreset, GlyphSet, KeyBindings, ScoringRules, ThemeName, WeaponMode,
+};
+
+/// @notice the knobs of a single game session.
+/// @dev Game2DMatrix::new uses the default settings, Game2DMatrix::with_settings takes custom ones.
+#[derive(Clone, Debug)]
+pub struct GameSettings {
+    pub lives: u8,
+    pub max_lives: u8,
+    // an extra life is granted every time the score passes a multiple of this value.
+    pub extra_life_score: u32,
+    // number of ticks the boat ignores collisions after a respawn.
+    pub invulnerable_ticks: u16,
+    // the tank size, the boat starts every life with a full tank.
+    pub fuel_capacity: u32,
+    // fuel gained every tick the boat hovers over a depot.
+    pub refuel_rate: u32,
+    // below this level the fuel low warning goes off.
+    pub fuel_low_level: u32,
+    pub difficulty: DifficultyPreset,
+    // (slowest, fastest) rows scrolled per tick.
+    pub throttle_range: (f32, f32),
+    pub throttle_step: f32,
+    // ticks between two shots, and while rapid fire is active.
+    pub fire_cooldown_ticks: u16,
+    pub rapid_fire_cooldown_ticks: u16,
+    pub weapon_mode: WeaponMode,
+    // rounds of a full gun in the ammo mode.
+    pub ammo_capacity: u32,
+    // rounds given by a supply crate and by a destroyed bridge.
+    pub ammo_per_supply: u32,
+    pub ammo_per_bridge: u32,
+    // heat added by a shot and lost every tick in the heat mode, the gun locks at max_heat.
+    pub heat_per_shot: f32,
+    pub heat_cooling: f32,
+    pub max_heat: f32,
+    // chance of a supply crate on a new row, crates only appear when the weapon is limited.
+    pub supply_chance: f64,
+    pub scoring: ScoringRules,
+    // the web server (host:port) the achievements are synced to, with the token of the account.
+    pub sync_server: Option<String>,
+    pub sync_token: Option<String>,
+    // the directory of the json summary of every run, and the csv file the runs are appended to.
+    pub stats_dir: String,
+    pub stats_csv: Option<String>,
+    // the colour theme, the glyphs and the colours of the terminal, detected when not chosen.
+    pub theme: Option<ThemeName>,
+    pub glyphs: Option<GlyphSet>,
+    pub colors: Option<ColorDepth>,
+    pub keys: KeyBindings,
+    // boats on the river, two share it in the co-op mode with the second player's keys.
+    pub players: u8,
+    pub second_keys: KeyBindings,
+    // the racers a hosted network race waits for, the host included, and the port it listens on.
+    pub racers: u8,
+    pub port: u16,
+    // the seed of the river, a random one when not set.
+    pub seed: Option<u64>,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        GameSettings {
+            lives: 3,
+            max_lives: 9,
+            extra_life_score: 5000,
+            invulnerable_ticks: 40,
+            fuel_capacity: 1500,
+            refuel_rate: 10,
+            fuel_low_level: 300,
+            difficulty: DifficultyPreset::default(),
+            throttle_range: (0.5, 2.0),
+            throttle_step: 0.25,
+            fire_cooldown_ticks: 4,
+            rapid_fire_cooldown_ticks: 1,
+            weapon_mode: WeaponMode::default(),
+            ammo_capacity: 60,
+            ammo_per_supply: 20,
+            ammo_per_bridge: 30,
+            heat_per_shot: 12.0,
+            heat_cooling: 1.5,
+            max_heat: 100.0,
+            supply_chance: 0.006,
+            scoring: ScoringRules::default(),
+            sync_server: None,
+            sync_token: None,
+            stats_dir: "stats".to_string(),
+            stats_csv: None,
+            theme: None,
+            glyphs: None,
+            colors: None,
+            keys: KeyBindings::default(),
+            players: 1,
+            second_keys: KeyBindings::second_player(),
+            racers: 2,
+            port: crate::netplay::DEFAULT_PORT,
+            seed: None,
+        }
+    }
+}
+
+impl GameSettings {
+    /// @notice reads the settings from the command line, i.e. `--difficulty hard --lives 5 --weapon ammo`.
+    pub fn from_args<I: Iterator<Item = String>>(mut args: I) -> Result<Self, String> {
+        let mut settings = GameSettings::default();
+
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for `{arg}`"))?;
+
+            match arg.as_str() {
+                "--difficulty" => settings.difficulty = value.parse()?,
+                "--weapon" => settings.weapon_mode = value.parse()?,
+                "--sync" => settings.sync_server = Some(value),
+                "--token" => settings.sync_token = Some(value),
+                "--stats-dir" => settings.stats_dir = value,
+                "--stats-csv" => settings.stats_csv = Some(value),
+                "--theme" => settings.theme = Some(value.parse()?),
+                "--glyphs" => settings.glyphs = Some(value.parse()?),
+                "--colors" => settings.colors = Some(value.parse()?),
+                "--bind" => settings.keys.bind(&value)?,
+                "--bind2" => settings.second_keys.bind(&value)?,
+                "--players" => {
+                    settings.players = match value.as_str() {
+                        "1" => 1,
+                        "2" => 2,
+                        _ => {
+                            return Err(format!(
+                                "invalid number of players `{value}`, expected 1 or 2"
+                            ))
+                        }
+                    }
+                }
+                "--racers" => {
+                    settings.racers = value
+                        .parse()
+                        .ok()
+                        .filter(|racers| (2..=crate::netplay::MAX_RACERS).contains(racers))
+                        .ok_or_else(|| {
+                            format!(
+                                "invalid number of racers `{value}`, expected 2 to {}",
+                                crate::netplay::MAX_RACERS
+                            )
+                        })?
+                }
+                "--port" => {
+                    settings.port = value
+                        .parse()
+                        .map_err(|_| format!("invalid port `{value}`"))?
+                }
+                "--seed" => {
+                    settings.seed = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("invalid seed `{value}`"))?,
+                    )
+                }
+                "--lives" => {
+                    settings.lives = value
+                        .parse()
+                        .ok()
+                        .filter(|lives| (1..=settings.max_lives).contains(lives))
+                        .ok_or_else(|| {
+                            format!(
+                                "invalid number of lives `{value}`, expected 1 to {}",
+                                settings.max_lives
+                            )
+                        })?
+                }
+                other => return Err(format!("unknown option `{other}`")),
+            }
+        }
+
+        // a key of the second boat can not also be one of the first player's keys.
+        if let Some(key) = settings
+            .second_keys
+            .action_keys()
+            .into_iter()
+            .find(|key| settings.keys.keys().contains(key))
+        {
+            return Err(format!(
+                "the key {key:?} of the second player is already bound to the first player"
+            ));
+        }
+
+        Ok(settings)
+    }
+}
@@ -0,0 +1,53 @@
+use crate::collision::Aabb;
+
+/// @notice a broad phase for the collisions, the indices of the entities bucketed by the rows
+/// their boxes cover.
+/// @dev rebuilt every tick, the buckets keep their allocations between the ticks.
+#[derive(Clone, Debug, Default)]
+pub struct RowIndex {
+    rows: Vec<Vec<usize>>,
+}
+
+impl RowIndex {
+    pub fn new(rows: usize) -> Self {
+        RowIndex {
+            rows: vec![Vec::new(); rows],
+        }
+    }
+
+    // the buckets covered by the box, clamped to the screen.
+    fn row_range(&self, aabb: &Aabb) -> std::ops::Range<usize> {
+        let last = self.rows.len();
+        let first = (aabb.top.floor().max(0.0) as usize).min(last);
+        let end = (aabb.bottom().ceil().max(0.0) as usize).clamp(first, last);
+        first..end
+    }
+
+    /// @notice empties the buckets and indexes the given boxes by their position in the iterator.
+    pub fn rebuild<I: IntoIterator<Item = Aabb>>(&mut self, boxes: I) {
+        for bucket in self.rows.iter_mut() {
+            bucket.clear();
+        }
+
+        for (idx, aabb) in boxes.into_iter().enumerate() {
+            self.insert(idx, &aabb);
+        }
+    }
+
+    pub fn insert(&mut self, idx: usize, aabb: &Aabb) {
+        for row in self.row_range(aabb) {
+            self.rows[row].push(idx);
+        }
+    }
+
+    /// @notice the indices of the entities sharing a row with the box, without duplicates.
+    /// @dev this is only a candidate list, the exact check is left to collision::hit.
+    pub fn query(&self, aabb: &Aabb, candidates: &mut Vec<usize>) {
+        candidates.clear();
+        for row in self.row_range(aabb) {
+            candidates.extend_from_slice(&self.rows[row]);
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+    }
+}
@@ -0,0 +1,286 @@
+use crate::collision::Body;
+use crate::ecs::{Components, Tag};
+use crate::powerup::PowerUpKind;
+use crate::weapon::WeaponMode;
+use crate::{EnemyKind, Game2DMatrix, Location, BRIDGE_INTERVAL, HUD_WIDTH};
+use rand::Rng;
+
+// new objects enter the river at this row.
+pub const SPAWN_ROW: u16 = 2;
+// rows below the spawn row checked for crowding.
+const CLUSTER_ROWS: u16 = 6;
+// columns of water the boat needs to get through a row.
+const MIN_PASSAGE: u16 = 6;
+// at most this many enemies inside the cluster window.
+const MAX_CLUSTER_ENEMIES: usize = 3;
+// rows kept free of other objects around a bridge.
+const BRIDGE_CLEARANCE: u16 = 4;
+// random columns tried before giving up on a placement.
+const PLACEMENT_ATTEMPTS: usize = 8;
+
+/// @notice decides what enters the river every time a new row scrolls in.
+/// @dev placements follow the stage of the difficulty, but the director guarantees a fuel
+/// depot at least every max_fuel_gap rows and never closes the passage for the boat.
+#[derive(Clone, Debug)]
+pub struct SpawnDirector {
+    pub max_fuel_gap: u32,
+    rows_since_fuel: u32,
+}
+
+impl Default for SpawnDirector {
+    fn default() -> Self {
+        SpawnDirector {
+            max_fuel_gap: 250,
+            rows_since_fuel: 0,
+        }
+    }
+}
+
+// the (column, width) spans taken by objects in the rows near the spawn row.
+fn occupied_spans(game: &Game2DMatrix) -> Vec<(u16, u16)> {
+    let window = SPAWN_ROW as f32..(SPAWN_ROW + CLUSTER_ROWS) as f32;
+
+    let world = &game.world;
+    world
+        .entities()
+        .into_iter()
+        .filter(|&entity| {
+            matches!(
+                world.tag.get(entity),
+                Some(Tag::Enemy | Tag::FuelDepot | Tag::PowerUp | Tag::Supply)
+            )
+                // jets fly away too fast to block anything.
+                && world.enemy.get(entity).map(|enemy| enemy.kind) != Some(EnemyKind::Jet)
+        })
+        .filter_map(|entity| {
+            let position = world.position.get(entity)?;
+            match world.body.get(entity)? {
+                Body::Cells(hitbox) if window.contains(&position.element_i) => {
+                    Some((position.element_j as u16, hitbox.width))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+// the widest run of free water between the banks once the given spans are taken.
+fn widest_passage(banks: (u16, u16), spans: &[(u16, u16)]) -> u16 {
+    let mut spans = spans.to_vec();
+    spans.sort_unstable();
+
+    let mut widest = 0;
+    let mut free_from = banks.0 + 1;
+    for (column, width) in spans {
+        widest = widest.max(column.saturating_sub(free_from));
+        free_from = free_from.max(column + width + 1);
+    }
+    widest.max(banks.1.saturating_sub(free_from))
+}
+
+impl SpawnDirector {
+    /// @notice the objects to add for the row that just scrolled in.
+    pub fn plan<R: Rng>(&mut self, rng: &mut R, game: &Game2DMatrix) -> Vec<Components> {
+        let banks = game.ground[SPAWN_ROW as usize];
+        let mut orders = vec![];
+
+        self.rows_since_fuel += 1;
+
+        if game.distance.is_multiple_of(BRIDGE_INTERVAL) {
+            orders.push(Components::bridge(Location::new(
+                SPAWN_ROW as f32,
+                banks.0 as f32,
+            )));
+            return orders;
+        }
+
+        // nothing spawns right before or after a bridge, it has to stay reachable for the bullets.
+        let near_bridge = game.world.tagged(Tag::Bridge).into_iter().any(|bridge| {
+            game.world
+                .position
+                .get(bridge)
+                .map(|position| position.element_i)
+                <= Some((SPAWN_ROW + BRIDGE_CLEARANCE) as f32)
+        }) || (game.distance % BRIDGE_INTERVAL)
+            > BRIDGE_INTERVAL - BRIDGE_CLEARANCE as u32;
+        if near_bridge {
+            return orders;
+        }
+
+        let mut spans = occupied_spans(game);
+
+        let fuel_due = self.rows_since_fuel >= self.max_fuel_gap;
+        if fuel_due || rng.gen_bool(game.stage.fuel_chance) {
+            if let Some(column) = self.place(rng, banks, 1, &spans) {
+                spans.push((column, 1));
+                self.rows_since_fuel = 0;
+                orders.push(Components::fuel_depot(Location::new(
+                    SPAWN_ROW as f32,
+                    column as f32,
+                )));
+            }
+        }
+
+        if rng.gen_bool(game.stage.power_up_chance) {
+            let width = 2;
+            if let Some(column) = self.place(rng, banks, width, &spans) {
+                spans.push((column, width));
+                orders.push(Components::power_up(
+                    PowerUpKind::pick(rng),
+                    Location::new(SPAWN_ROW as f32, column as f32),
+                ));
+            }
+        }
+
+        // the crates are only useful when the gun can run dry.
+        let limited = game.settings.weapon_mode != WeaponMode::Unlimited;
+        if limited && rng.gen_bool(game.settings.supply_chance) {
+            let width = 2;
+            if let Some(column) = self.place(rng, banks, width, &spans) {
+                spans.push((column, width));
+                orders.push(Components::supply_crate(Location::new(
+                    SPAWN_ROW as f32,
+                    column as f32,
+                )));
+            }
+        }
+
+        let crowded = game
+            .world
+            .tagged(Tag::Enemy)
+            .into_iter()
+            .filter_map(|enemy| game.world.position.get(enemy))
+            .filter(|position| position.element_i < (SPAWN_ROW + CLUSTER_ROWS) as f32)
+            .count()
+            >= MAX_CLUSTER_ENEMIES;
+
+        if !crowded && rng.gen_bool(game.stage.enemy_chance) {
+            let kind = EnemyKind::pick(rng, game.stage.enemy_mix);
+            let width = kind.behaviour().hitbox().width;
+            let direction = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+
+            // jets come from the side of the river field instead of the water.
+            let column = match kind {
+                EnemyKind::Jet if direction > 0.0 => Some(HUD_WIDTH + 1),
+                EnemyKind::Jet => Some(game.max_screen_i.saturating_sub(width + 1)),
+                _ => self.place(rng, banks, width, &spans),
+            };
+
+            if let Some(column) = column {
+                orders.push(Components::enemy(
+                    kind,
+                    Location::new(SPAWN_ROW as f32, column as f32),
+                    direction,
+                ));
+            }
+        }
+
+        orders
+    }
+
+    // a random free column for an object of the given width, which keeps a passage for the boat.
+    fn place<R: Rng>(
+        &self,
+        rng: &mut R,
+        banks: (u16, u16),
+        width: u16,
+        spans: &[(u16, u16)],
+    ) -> Option<u16> {
+        let (first, last) = (banks.0 + 2, banks.1.saturating_sub(width + 1));
+        if first >= last {
+            return None;
+        }
+
+        (0..PLACEMENT_ATTEMPTS)
+            .map(|_| rng.gen_range(first..last))
+            .find(|&column| {
+                let overlaps = spans.iter().any(|&(taken, taken_width)| {
+                    column <= taken + taken_width && taken <= column + width
+                });
+
+                let mut with_candidate = spans.to_vec();
+                with_candidate.push((column, width));
+
+                !overlaps && widest_passage(banks, &with_candidate) >= MIN_PASSAGE
+            })
+    }
+}
+
+impl Game2DMatrix {
+    /// @notice asks the spawn director for the objects of the new row and adds them to the river.
+    pub fn spawn(&mut self) {
+        let mut director = std::mem::take(&mut self.spawn_director);
+        let mut rng = self.rng.clone();
+        let orders = director.plan(&mut rng, self);
+        self.spawn_director = director;
+        self.rng = rng;
+
+        for components in orders {
+            self.world.spawn(components);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameSettings;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // a game at a row far from the bridges where nothing spawns on chance.
+    fn quiet_game() -> Game2DMatrix {
+        let settings = GameSettings {
+            seed: Some(3),
+            ..GameSettings::default()
+        };
+        let mut game = Game2DMatrix::with_screen(settings, 120, 40);
+        game.ground.fill((50, 90));
+        game.distance = 10;
+        game.stage.fuel_chance = 0.0;
+        game.stage.enemy_chance = 0.0;
+        game.stage.power_up_chance = 0.0;
+        game
+    }
+
+    #[test]
+    fn widest_passage_is_the_widest_free_run() {
+        assert_eq!(widest_passage((10, 40), &[]), 29);
+        assert_eq!(widest_passage((10, 40), &[(20, 2)]), 17);
+        assert_eq!(widest_passage((10, 40), &[(12, 4), (30, 4)]), 13);
+    }
+
+    #[test]
+    fn a_fuel_depot_comes_after_max_fuel_gap_rows() {
+        let game = quiet_game();
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut director = SpawnDirector {
+            max_fuel_gap: 20,
+            ..SpawnDirector::default()
+        };
+
+        let rows = (1..=20)
+            .find(|_| {
+                director
+                    .plan(&mut rng, &game)
+                    .iter()
+                    .any(|components| components.tag == Tag::FuelDepot)
+            })
+            .expect("a fuel depot within max_fuel_gap rows");
+        assert_eq!(rows, 20);
+    }
+
+    #[test]
+    fn placements_keep_a_passage_open() {
+        let director = SpawnDirector::default();
+        let mut rng = StdRng::seed_from_u64(7);
+        let banks = (50, 70);
+        let mut spans = vec![];
+        for _ in 0..100 {
+            if let Some(column) = director.place(&mut rng, banks, 3, &spans) {
+                spans.push((column, 3));
+            }
+            assert!(widest_passage(banks, &spans) >= MIN_PASSAGE);
+        }
+        assert!(!spans.is_empty());
+    }
+}
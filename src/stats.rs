@@ -0,0 +1,130 @@
+use crate::events::{DeathCause, GameEvent};
+use crate::{Game2DMatrix, Player};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// the columns of the csv history, in the order of RunStats::csv_row.
+const CSV_HEADER: &str = "started_at,difficulty,weapon,score,shots_fired,bullets_hit,accuracy,\
+rows_travelled,fuel_collected,time_alive_ms,closest_bank,enemies_killed,bridges_destroyed,\
+depots_shot,power_ups_collected,lives_lost,cause_of_death";
+
+/// @notice the statistics of a single run, used to tune the difficulty.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunStats {
+    // unix timestamp of the start of the run.
+    pub started_at: i64,
+    pub difficulty: String,
+    pub weapon: String,
+    pub score: u32,
+    // bullets fired, a spread shot counts all of its bullets.
+    pub shots_fired: u32,
+    pub bullets_hit: u32,
+    // bullets_hit / shots_fired, zero without any shot.
+    pub accuracy: f32,
+    // rows of river travelled, including the ones travelled again after a respawn.
+    pub rows_travelled: u32,
+    pub fuel_collected: u32,
+    pub time_alive_ms: u64,
+    // the fewest columns between the boat and a bank, none while the boat never was on the river.
+    pub closest_bank: Option<f32>,
+    pub enemies_killed: u32,
+    pub bridges_destroyed: u32,
+    pub depots_shot: u32,
+    pub power_ups_collected: u32,
+    // what took every life, in order.
+    pub deaths: Vec<DeathCause>,
+    // what ended the run, none when the player quit.
+    pub cause_of_death: Option<DeathCause>,
+}
+
+impl RunStats {
+    pub fn new(started_at: i64) -> Self {
+        RunStats {
+            started_at,
+            ..Default::default()
+        }
+    }
+
+    /// @notice counts the events of a tick.
+    pub fn observe(&mut self, events: &[GameEvent]) {
+        for event in events {
+            match *event {
+                GameEvent::Tick { ms } => self.time_alive_ms += ms,
+                GameEvent::RowTravelled => self.rows_travelled += 1,
+                GameEvent::ShotFired { bullets } => self.shots_fired += bullets,
+                GameEvent::BulletHit => self.bullets_hit += 1,
+                GameEvent::EnemyKilled(_) => self.enemies_killed += 1,
+                GameEvent::BridgeDestroyed => self.bridges_destroyed += 1,
+                GameEvent::DepotShot => self.depots_shot += 1,
+                GameEvent::Refuelled { fuel } => self.fuel_collected += fuel,
+                GameEvent::PowerUpCollected(_) => self.power_ups_collected += 1,
+                GameEvent::BankClearance(columns) => {
+                    self.closest_bank = Some(self.closest_bank.map_or(columns, |c| c.min(columns)));
+                }
+                GameEvent::LifeLost(cause) => self.deaths.push(cause),
+            }
+        }
+    }
+
+    /// @notice the csv line of the run, see CSV_HEADER.
+    fn csv_row(&self) -> String {
+        let cause = |cause: Option<DeathCause>| cause.map(|c| format!("{c:?}")).unwrap_or_default();
+        format!(
+            "{},{},{},{},{},{},{:.3},{},{},{},{},{},{},{},{},{},{}",
+            self.started_at,
+            self.difficulty,
+            self.weapon,
+            self.score,
+            self.shots_fired,
+            self.bullets_hit,
+            self.accuracy,
+            self.rows_travelled,
+            self.fuel_collected,
+            self.time_alive_ms,
+            self.closest_bank.map(|c| c.to_string()).unwrap_or_default(),
+            self.enemies_killed,
+            self.bridges_destroyed,
+            self.depots_shot,
+            self.power_ups_collected,
+            self.deaths.len(),
+            cause(self.cause_of_death),
+        )
+    }
+}
+
+impl Game2DMatrix {
+    /// @notice the statistics of the run once it is over, with what only the game knows at the end.
+    pub fn finish_stats(&self) -> RunStats {
+        let mut stats = self.stats.clone();
+        stats.difficulty = format!("{:?}", self.settings.difficulty).to_lowercase();
+        stats.weapon = format!("{:?}", self.settings.weapon_mode).to_lowercase();
+        stats.score = self.score();
+        if stats.shots_fired > 0 {
+            stats.accuracy = stats.bullets_hit as f32 / stats.shots_fired as f32;
+        }
+        if self.players.iter().all(Player::is_out) {
+            stats.cause_of_death = stats.deaths.last().copied();
+        }
+        stats
+    }
+}
+
+/// @notice writes the summary of a run as `run-<started_at>.json` in the directory, returns its path.
+pub fn write_summary(dir: &Path, stats: &RunStats) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("run-{}.json", stats.started_at));
+    fs::write(&path, serde_json::to_string_pretty(stats)?)?;
+    Ok(path)
+}
+
+/// @notice appends the run to the csv history, the header is written with the first run.
+pub fn append_history(path: &Path, stats: &RunStats) -> io::Result<()> {
+    let new_file = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if new_file {
+        writeln!(file, "{CSV_HEADER}")?;
+    }
+    writeln!(file, "{}", stats.csv_row())
+}
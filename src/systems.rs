@@ -0,0 +1,450 @@
+use crate::collision::{self, Aabb};
+use crate::ecs::{Entity, Layout, Tag};
+use crate::events::{DeathCause, GameEvent};
+use crate::powerup::PowerUpKind;
+use crate::theme::{Colors, Paint};
+use crate::{enemy, Game2DMatrix, Location, Player, Velocity, HUD_WIDTH};
+use crossterm::{
+    cursor::MoveTo,
+    style::{Print, ResetColor},
+    QueueableCommand,
+};
+use rand::Rng;
+use std::io::{Result, Stdout};
+
+// the player's bullets leave the game above this row.
+const TOP_ROW: f32 = 3.0;
+
+/// @notice what the boat touched during a tick.
+#[derive(Clone, Debug, Default)]
+pub struct Contacts {
+    // the first enemy, enemy bullet or bridge that hit the boat.
+    pub crash: Option<DeathCause>,
+    // the boat is over a fuel depot.
+    pub refuelling: bool,
+    pub power_ups: Vec<PowerUpKind>,
+    // supply crates picked up.
+    pub supplies: u32,
+}
+
+impl Game2DMatrix {
+    /// @notice the banks of a row, rows outside the screen use the nearest one.
+    pub fn banks_at(&self, row: f32) -> (u16, u16) {
+        let last_row = self.ground.len() - 1;
+        self.ground[(row.max(0.0) as usize).min(last_row)]
+    }
+
+    pub fn entity_box(&self, entity: Entity) -> Option<Aabb> {
+        let position = self.world.position.get(entity)?;
+        let body = self.world.body.get(entity)?;
+        Some(body.aabb(position, self.banks_at(position.element_i)))
+    }
+
+    /// @notice how far an entity moved during the last tick.
+    /// @dev its own velocity, plus the river scrolling for the objects on the water.
+    pub fn entity_motion(&self, entity: Entity) -> Velocity {
+        let velocity = self.world.velocity.get(entity).copied().unwrap_or_default();
+        let scrolled = match self.world.tag.get(entity) {
+            Some(tag) if tag.scrolls() => self.rows_scrolled as f32,
+            _ => 0.0,
+        };
+        Velocity::new(velocity.di + scrolled, velocity.dj)
+    }
+
+    /////////////////////////////// Movement ///////////////////////////////
+
+    /// @notice the behaviour system, every enemy steers by setting its velocity.
+    pub fn steer_enemies(&mut self) {
+        for entity in self.world.entities() {
+            let Some(&location) = self.world.position.get(entity) else {
+                continue;
+            };
+            let ctx = enemy::BehaviourContext {
+                banks: self.banks_at(location.element_i),
+                max_screen_i: self.max_screen_i,
+                tick: self.tick,
+            };
+
+            let world = &mut self.world;
+            if let (Some(enemy), Some(velocity)) =
+                (world.enemy.get_mut(entity), world.velocity.get_mut(entity))
+            {
+                enemy
+                    .kind
+                    .behaviour()
+                    .advance(enemy, &location, velocity, &ctx);
+            }
+        }
+    }
+
+    /// @notice the movement system, moves every entity by its velocity.
+    pub fn move_entities(&mut self) {
+        let world = &mut self.world;
+        for entity in world.entities() {
+            if let (Some(position), Some(velocity)) =
+                (world.position.get_mut(entity), world.velocity.get(entity))
+            {
+                position.integrate(*velocity);
+            }
+        }
+    }
+
+    /// @notice moves the objects on the water down with the river by one row.
+    pub fn scroll_entities(&mut self) {
+        let world = &mut self.world;
+        for entity in world.entities() {
+            if world.tag.get(entity).is_some_and(|tag| tag.scrolls()) {
+                if let Some(position) = world.position.get_mut(entity) {
+                    position.element_i += 1.0;
+                }
+            }
+        }
+    }
+
+    /// @notice every enemy may fire at the closest boat following the pattern of its kind.
+    pub fn enemy_fire(&mut self) {
+        let boats: Vec<Location> = self
+            .players
+            .iter()
+            .filter(|player| player.is_sailing())
+            .map(|player| Location::new(player.j as f32, player.i as f32))
+            .collect();
+        if boats.is_empty() {
+            return;
+        }
+
+        let mut shots = vec![];
+        for entity in self.world.entities() {
+            if let (Some(enemy), Some(position)) = (
+                self.world.enemy.get(entity),
+                self.world.position.get(entity),
+            ) {
+                if self.rng.gen_bool(self.stage.enemy_fire_rate) {
+                    let target = boats
+                        .iter()
+                        .min_by(|a, b| {
+                            let distance =
+                                |boat: &Location| (boat.element_j - position.element_j).abs();
+                            distance(a).total_cmp(&distance(b))
+                        })
+                        .copied()
+                        .unwrap_or_default();
+                    shots.extend(
+                        enemy
+                            .kind
+                            .behaviour()
+                            .fire_pattern()
+                            .fire(position, &target),
+                    );
+                }
+            }
+        }
+
+        for shot in shots {
+            self.world.spawn(shot);
+        }
+    }
+
+    /////////////////////////////// Collision ///////////////////////////////
+
+    /// @notice matches the players' bullets against everything with health left, every hit takes
+    /// one health from the target and spends the bullet unless it is piercing.
+    /// @dev returns the entities whose health ran out, each one once, with the player who shot them.
+    /// the targets are looked up through the row index, so a bullet is only checked against the
+    /// entities on its rows.
+    pub fn resolve_bullet_hits(&mut self) -> Vec<(Entity, usize)> {
+        let targets: Vec<(Entity, Aabb, Velocity)> = self
+            .world
+            .entities()
+            .into_iter()
+            .filter(|&entity| {
+                self.world
+                    .health
+                    .get(entity)
+                    .is_some_and(|&health| health > 0)
+            })
+            .filter_map(|entity| {
+                Some((entity, self.entity_box(entity)?, self.entity_motion(entity)))
+            })
+            .collect();
+        self.enemy_index
+            .rebuild(targets.iter().map(|(_, aabb, motion)| aabb.swept(*motion)));
+
+        let mut destroyed: Vec<(Entity, usize)> = vec![];
+        let mut candidates: Vec<usize> = vec![];
+
+        for bullet in self.world.tagged(Tag::PlayerBullet) {
+            let (Some(bullet_box), Some(&velocity)) =
+                (self.entity_box(bullet), self.world.velocity.get(bullet))
+            else {
+                continue;
+            };
+            self.enemy_index
+                .query(&bullet_box.swept(velocity), &mut candidates);
+
+            // a piercing bullet hits everything on its way, any other one stops at the first target.
+            let piercing = self.world.piercing.get(bullet).is_some();
+            let shooter = self.world.owner.get(bullet).copied().unwrap_or(0);
+            let hits: Vec<Entity> = candidates
+                .iter()
+                .map(|&idx| targets[idx])
+                .filter(|&(entity, target_box, target_motion)| {
+                    self.world
+                        .health
+                        .get(entity)
+                        .is_some_and(|&health| health > 0)
+                        && collision::hit(bullet_box, velocity, target_box, target_motion)
+                })
+                .map(|(entity, _, _)| entity)
+                .take(if piercing { usize::MAX } else { 1 })
+                .collect();
+
+            if !hits.is_empty() {
+                self.events.push(GameEvent::BulletHit);
+                if !piercing {
+                    self.world.despawn(bullet);
+                }
+            }
+            for entity in hits {
+                if let Some(health) = self.world.health.get_mut(entity) {
+                    *health -= 1;
+                    if *health == 0 {
+                        destroyed.push((entity, shooter));
+                    }
+                }
+            }
+        }
+
+        destroyed
+    }
+
+    /// @notice what the boat touched during the last tick.
+    /// @dev the entities spent on contact, like the enemies' bullets and the power-ups, are removed.
+    pub fn boat_contacts(&mut self, boat: Aabb) -> Contacts {
+        // the boat stays on its row, the entities' motion is relative to it.
+        let still = Velocity::default();
+        let mut contacts = Contacts::default();
+
+        for entity in self.world.entities() {
+            let (Some(&tag), Some(entity_box)) =
+                (self.world.tag.get(entity), self.entity_box(entity))
+            else {
+                continue;
+            };
+
+            if tag == Tag::FuelDepot && entity_box.intersects(&boat) {
+                contacts.refuelling = true;
+            }
+
+            if !collision::hit(entity_box, self.entity_motion(entity), boat, still) {
+                continue;
+            }
+
+            if tag.harms_boat() {
+                contacts.crash.get_or_insert(match tag {
+                    Tag::EnemyBullet => DeathCause::EnemyBullet,
+                    Tag::Bridge => DeathCause::Bridge,
+                    _ => DeathCause::Enemy,
+                });
+            }
+            if let Some(&kind) = self.world.power_up.get(entity) {
+                contacts.power_ups.push(kind);
+            }
+            if tag == Tag::Supply {
+                contacts.supplies += 1;
+            }
+            if tag.spent_on_contact() {
+                self.world.despawn(entity);
+            }
+        }
+
+        contacts
+    }
+
+    /////////////////////////////// Animation ///////////////////////////////
+
+    /// @notice the animation system, moves every animation a tick forward and removes the finished ones.
+    pub fn animate_entities(&mut self) {
+        for entity in self.world.entities() {
+            let Some(animation) = self.world.animation.get_mut(entity) else {
+                continue;
+            };
+            animation.age += 1;
+            if animation.is_over() {
+                self.world.despawn(entity);
+                continue;
+            }
+
+            let (glyph, ascii) = animation.frame();
+            if let Some(sprite) = self.world.sprite.get_mut(entity) {
+                sprite.glyph = glyph.to_string();
+                sprite.ascii = ascii.to_string();
+            }
+        }
+    }
+
+    /////////////////////////////// Cleanup ///////////////////////////////
+
+    /// @notice the cleanup system, removes the entities that left the screen or were destroyed.
+    /// @dev the objects flying over the banks, like the jets, leave at the sides of the screen.
+    pub fn cleanup_entities(&mut self) {
+        let (max_screen_i, bottom) = (self.max_screen_i as f32, (self.max_screen_j - 3) as f32);
+
+        for entity in self.world.entities() {
+            let destroyed = self.world.health.get(entity) == Some(&0);
+            let climbing = self
+                .world
+                .velocity
+                .get(entity)
+                .is_some_and(|velocity| velocity.di < 0.0);
+            let gone = match (self.world.position.get(entity), self.entity_box(entity)) {
+                (Some(position), Some(aabb)) => {
+                    position.element_i >= bottom
+                        || (climbing && position.element_i < TOP_ROW)
+                        || aabb.left < HUD_WIDTH as f32
+                        || aabb.right() > max_screen_i
+                }
+                _ => true,
+            };
+
+            if destroyed || gone {
+                self.world.despawn(entity);
+            }
+        }
+    }
+
+    /////////////////////////////// Rendering ///////////////////////////////
+
+    /// @notice the colours of the water at a cell, shaded by its distance to the banks.
+    pub fn water_at(&self, column: u16, row: f32) -> Colors {
+        let (left, right) = self.banks_at(row);
+        let from_bank = column
+            .saturating_sub(left)
+            .min(right.saturating_sub(column + 1));
+        self.theme.water(from_bank, (right - left) / 2)
+    }
+
+    /// @notice draws the banks and the water of a row.
+    /// @dev the cells are printed in runs of the same colours, a flat theme prints three runs.
+    pub fn draw_river_row(&self, screen: &mut Stdout, row: usize) -> Result<()> {
+        let (left, right) = self.ground[row];
+        let bank = self.theme.bank_fill();
+
+        screen.queue(MoveTo(HUD_WIDTH, row as u16))?;
+        let mut run = String::new();
+        let mut run_colors = None;
+        for column in HUD_WIDTH..self.max_screen_i {
+            let (colors, cell) = if self.damage_flash > 0 {
+                (
+                    self.theme.damage_flash(),
+                    if column < left || column >= right {
+                        bank
+                    } else {
+                        ' '
+                    },
+                )
+            } else if column < left {
+                (self.theme.bank(left - 1 - column), bank)
+            } else if column >= right {
+                (self.theme.bank(column - right), bank)
+            } else {
+                (self.water_at(column, row as f32), ' ')
+            };
+
+            if run_colors != Some(colors) {
+                if let Some(run_colors) = run_colors {
+                    screen
+                        .queue(run_colors)?
+                        .queue(Print(&run))?
+                        .queue(ResetColor)?;
+                }
+                run.clear();
+                run_colors = Some(colors);
+            }
+            run.push(cell);
+        }
+        if let Some(run_colors) = run_colors {
+            screen
+                .queue(run_colors)?
+                .queue(Print(&run))?
+                .queue(ResetColor)?;
+        }
+
+        Ok(())
+    }
+
+    /// @notice the render system, draws the sprite of every entity.
+    pub fn draw_entities(&self, screen: &mut Stdout) -> Result<()> {
+        for entity in self.world.entities() {
+            let (Some(sprite), Some(position)) = (
+                self.world.sprite.get(entity),
+                self.world.position.get(entity),
+            ) else {
+                continue;
+            };
+            let (column, top) = position.screen_cell();
+            let glyph = self.theme.glyph(&sprite.glyph, &sprite.ascii);
+
+            // what floats on the water takes the shade of the water around it.
+            screen.queue(match sprite.paint {
+                Paint::Water => self.water_at(column, position.element_i),
+                paint => self.theme.paint(paint),
+            })?;
+            if let Some(animation) = self.world.animation.get(entity) {
+                screen.queue(self.theme.explosion(animation.progress()))?;
+            }
+
+            match sprite.layout {
+                Layout::Inline => {
+                    screen.queue(MoveTo(column, top))?.queue(Print(glyph))?;
+                }
+                Layout::Vertical => {
+                    for (k, letter) in glyph.chars().enumerate() {
+                        let row = top + k as u16;
+                        if row >= self.max_screen_j {
+                            break;
+                        }
+                        screen.queue(MoveTo(column, row))?.queue(Print(letter))?;
+                    }
+                }
+                Layout::Span => {
+                    let (left, right) = self.banks_at(position.element_i);
+                    screen
+                        .queue(MoveTo(left, top))?
+                        .queue(Print(glyph.repeat((right - left) as usize)))?;
+                }
+            }
+
+            screen.queue(ResetColor)?;
+        }
+
+        Ok(())
+    }
+
+    /// @notice draws the boat of another run of the river over this one, faded and marked with its label.
+    /// @dev the ghost is placed by the distance its run travelled, above the boat when it is further up
+    /// the river. a ghost off the screen, wrecked or out of the game is not drawn.
+    pub fn draw_ghost(
+        &self,
+        screen: &mut Stdout,
+        ghost: &Player,
+        distance: u32,
+        label: char,
+    ) -> Result<()> {
+        let row = ghost.j as i64 - (distance as i64 - self.distance as i64);
+        if !ghost.is_sailing() || row < TOP_ROW as i64 || row >= self.max_screen_j as i64 {
+            return Ok(());
+        }
+        let column = ghost
+            .i
+            .clamp(HUD_WIDTH, self.max_screen_i.saturating_sub(2));
+
+        screen
+            .queue(MoveTo(column, row as u16))?
+            .queue(self.water_at(column, row as f32))?
+            .queue(self.theme.paint(Paint::Ghost))?
+            .queue(Print(format!("{}{}", self.theme.glyph("△", "^"), label)))?
+            .queue(ResetColor)?;
+        Ok(())
+    }
+}
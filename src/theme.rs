@@ -0,0 +1,501 @@
+use crate::GameSettings;
+use crossterm::{
+    style::{Color, SetBackgroundColor, SetForegroundColor},
+    Command,
+};
+use std::fmt;
+use std::str::FromStr;
+
+/// @notice the colour themes of the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ThemeName {
+    #[default]
+    Classic,
+    HighContrast,
+    // no red against green, the colours stay apart for the common kinds of colour blindness.
+    ColourblindSafe,
+    // the terminal's own colours, the banks are drawn with a fill character instead.
+    Monochrome,
+}
+
+impl FromStr for ThemeName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "classic" => Ok(ThemeName::Classic),
+            "high-contrast" => Ok(ThemeName::HighContrast),
+            "colourblind" | "colorblind" => Ok(ThemeName::ColourblindSafe),
+            "monochrome" => Ok(ThemeName::Monochrome),
+            other => Err(format!(
+                "unknown theme `{other}`, expected classic, high-contrast, colourblind or monochrome"
+            )),
+        }
+    }
+}
+
+/// @notice the glyphs the sprites are drawn with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum GlyphSet {
+    #[default]
+    Emoji,
+    // plain ascii, for the terminals and fonts without emoji or with narrow ones.
+    Ascii,
+}
+
+impl FromStr for GlyphSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "emoji" => Ok(GlyphSet::Emoji),
+            "ascii" => Ok(GlyphSet::Ascii),
+            other => Err(format!("unknown glyphs `{other}`, expected emoji or ascii")),
+        }
+    }
+}
+
+/// @notice how many colours the terminal can show.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ColorDepth {
+    // the 16 named colours, the palettes are made of them.
+    #[default]
+    Ansi16,
+    Ansi256,
+    // 24 bit colours.
+    TrueColor,
+}
+
+impl FromStr for ColorDepth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "16" => Ok(ColorDepth::Ansi16),
+            "256" => Ok(ColorDepth::Ansi256),
+            "truecolor" | "24bit" => Ok(ColorDepth::TrueColor),
+            other => Err(format!(
+                "unknown colours `{other}`, expected 16, 256 or truecolor"
+            )),
+        }
+    }
+}
+
+impl ColorDepth {
+    /// @notice the closest colour the terminal can show, none with only the named colours.
+    pub fn rgb(self, (r, g, b): Rgb) -> Option<Color> {
+        // the 6x6x6 colour cube of the 256 colours starts at 16.
+        let cube = |value: u8| (value as u16 * 5 + 127) / 255;
+        match self {
+            ColorDepth::Ansi16 => None,
+            ColorDepth::Ansi256 => Some(Color::AnsiValue(
+                (16 + 36 * cube(r) + 6 * cube(g) + cube(b)) as u8,
+            )),
+            ColorDepth::TrueColor => Some(Color::Rgb { r, g, b }),
+        }
+    }
+}
+
+pub type Rgb = (u8, u8, u8);
+
+// steps of the gradients, fewer steps keep the colour changes of a row, and the output, short.
+const SHADES: f32 = 6.0;
+// columns of a bank shaded from the shore to the inland colour.
+const SHORE_WIDTH: f32 = 3.0;
+
+fn lerp(from: Rgb, to: Rgb, t: f32) -> Rgb {
+    let t = (t.clamp(0.0, 1.0) * (SHADES - 1.0)).round() / (SHADES - 1.0);
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    (mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+/// @notice the ends of the gradients of a theme, drawn when the terminal has more than 16 colours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gradients {
+    // the water at the banks and in the middle of the river.
+    pub shallow_water: Rgb,
+    pub deep_water: Rgb,
+    // the bank right at the water, fading into the inland colour away from it.
+    pub shore: Rgb,
+    pub inland: Rgb,
+}
+
+// an explosion goes from a white flash to dark embers.
+const EXPLOSION: [Rgb; 4] = [(255, 255, 255), (255, 200, 0), (200, 30, 0), (60, 10, 0)];
+const EXPLOSION_16: [Color; 4] = [Color::White, Color::Yellow, Color::Red, Color::DarkRed];
+const DAMAGE_FLASH: Rgb = (170, 20, 20);
+
+/// @notice what a piece of the screen is, the theme decides its colours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Paint {
+    Bank,
+    // the river and everything floating on it without colours of its own.
+    Water,
+    Hud,
+    // the warnings of the HUD, i.e. the fuel low and the locked gun.
+    Alert,
+    // the achievement unlocked notice.
+    Notice,
+    Bridge,
+    FuelDepot,
+    EnemyBullet,
+    Unlocked,
+    Locked,
+    // the fuel and heat gauges of the HUD, from a full tank down to the warning level.
+    GaugeHigh,
+    GaugeMid,
+    GaugeLow,
+    // the boats of the other racers, drawn faded over the water.
+    Ghost,
+}
+
+/// @notice a foreground and a background colour, queued like any other crossterm command.
+/// @dev a missing colour keeps the one already set, the monochrome theme sets none.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Colors {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+}
+
+impl Colors {
+    const NONE: Colors = Colors {
+        foreground: None,
+        background: None,
+    };
+
+    const fn on(background: Color) -> Self {
+        Colors {
+            foreground: None,
+            background: Some(background),
+        }
+    }
+
+    fn gradient(foreground: Option<Color>, background: Option<Color>) -> Self {
+        Colors {
+            foreground,
+            background,
+        }
+    }
+
+    const fn pair(foreground: Color, background: Color) -> Self {
+        Colors {
+            foreground: Some(foreground),
+            background: Some(background),
+        }
+    }
+
+    const fn text(foreground: Color) -> Self {
+        Colors {
+            foreground: Some(foreground),
+            background: None,
+        }
+    }
+}
+
+impl Command for Colors {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        if let Some(color) = self.foreground {
+            SetForegroundColor(color).write_ansi(f)?;
+        }
+        if let Some(color) = self.background {
+            SetBackgroundColor(color).write_ansi(f)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        if let Some(color) = self.foreground {
+            SetForegroundColor(color).execute_winapi()?;
+        }
+        if let Some(color) = self.background {
+            SetBackgroundColor(color).execute_winapi()?;
+        }
+        Ok(())
+    }
+}
+
+/// @notice the colours of every paint of a theme.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Palette {
+    pub bank: Colors,
+    pub water: Colors,
+    pub hud: Colors,
+    pub alert: Colors,
+    pub notice: Colors,
+    pub bridge: Colors,
+    pub fuel_depot: Colors,
+    pub enemy_bullet: Colors,
+    pub unlocked: Colors,
+    pub locked: Colors,
+    pub gauge_high: Colors,
+    pub gauge_mid: Colors,
+    pub gauge_low: Colors,
+    pub ghost: Colors,
+    // the high contrast and the monochrome themes stay flat.
+    pub gradients: Option<Gradients>,
+}
+
+const CLASSIC: Palette = Palette {
+    bank: Colors::pair(Color::Green, Color::Green),
+    water: Colors::on(Color::Blue),
+    hud: Colors::on(Color::DarkGrey),
+    alert: Colors::pair(Color::White, Color::Red),
+    notice: Colors::pair(Color::Black, Color::Yellow),
+    bridge: Colors::pair(Color::Black, Color::DarkYellow),
+    fuel_depot: Colors::pair(Color::White, Color::Red),
+    enemy_bullet: Colors::pair(Color::Red, Color::Blue),
+    unlocked: Colors::text(Color::Yellow),
+    locked: Colors::text(Color::Grey),
+    gauge_high: Colors::pair(Color::Green, Color::DarkGrey),
+    gauge_mid: Colors::pair(Color::Yellow, Color::DarkGrey),
+    gauge_low: Colors::pair(Color::Red, Color::DarkGrey),
+    ghost: Colors::text(Color::Grey),
+    gradients: Some(Gradients {
+        shallow_water: (40, 110, 210),
+        deep_water: (0, 35, 130),
+        shore: (194, 178, 128),
+        inland: (30, 120, 40),
+    }),
+};
+
+const HIGH_CONTRAST: Palette = Palette {
+    bank: Colors::pair(Color::White, Color::White),
+    water: Colors::on(Color::Black),
+    hud: Colors::pair(Color::Black, Color::White),
+    alert: Colors::pair(Color::Black, Color::Yellow),
+    notice: Colors::pair(Color::Black, Color::Cyan),
+    bridge: Colors::pair(Color::Black, Color::Yellow),
+    fuel_depot: Colors::pair(Color::Black, Color::Magenta),
+    enemy_bullet: Colors::pair(Color::Yellow, Color::Black),
+    unlocked: Colors::text(Color::Yellow),
+    locked: Colors::text(Color::White),
+    gauge_high: Colors::pair(Color::Black, Color::White),
+    gauge_mid: Colors::pair(Color::Black, Color::Cyan),
+    gauge_low: Colors::pair(Color::Black, Color::Yellow),
+    ghost: Colors::text(Color::Cyan),
+    gradients: None,
+};
+
+const COLOURBLIND_SAFE: Palette = Palette {
+    bank: Colors::pair(Color::DarkYellow, Color::DarkYellow),
+    water: Colors::on(Color::DarkBlue),
+    hud: Colors::pair(Color::White, Color::DarkGrey),
+    alert: Colors::pair(Color::Black, Color::Yellow),
+    notice: Colors::pair(Color::Black, Color::Cyan),
+    bridge: Colors::pair(Color::Black, Color::White),
+    fuel_depot: Colors::pair(Color::Black, Color::Cyan),
+    enemy_bullet: Colors::pair(Color::Yellow, Color::DarkBlue),
+    unlocked: Colors::text(Color::Cyan),
+    locked: Colors::text(Color::Grey),
+    gauge_high: Colors::pair(Color::Cyan, Color::DarkGrey),
+    gauge_mid: Colors::pair(Color::White, Color::DarkGrey),
+    gauge_low: Colors::pair(Color::Black, Color::Yellow),
+    ghost: Colors::text(Color::Grey),
+    gradients: Some(Gradients {
+        shallow_water: (86, 180, 233),
+        deep_water: (0, 60, 130),
+        shore: (240, 228, 66),
+        inland: (150, 110, 20),
+    }),
+};
+
+const MONOCHROME: Palette = Palette {
+    bank: Colors::NONE,
+    water: Colors::NONE,
+    hud: Colors::NONE,
+    alert: Colors::NONE,
+    notice: Colors::NONE,
+    bridge: Colors::NONE,
+    fuel_depot: Colors::NONE,
+    enemy_bullet: Colors::NONE,
+    unlocked: Colors::NONE,
+    locked: Colors::NONE,
+    gauge_high: Colors::NONE,
+    gauge_mid: Colors::NONE,
+    gauge_low: Colors::NONE,
+    ghost: Colors::NONE,
+    gradients: None,
+};
+
+impl Palette {
+    pub fn of(name: ThemeName) -> Palette {
+        match name {
+            ThemeName::Classic => CLASSIC,
+            ThemeName::HighContrast => HIGH_CONTRAST,
+            ThemeName::ColourblindSafe => COLOURBLIND_SAFE,
+            ThemeName::Monochrome => MONOCHROME,
+        }
+    }
+
+    pub fn get(&self, paint: Paint) -> Colors {
+        match paint {
+            Paint::Bank => self.bank,
+            Paint::Water => self.water,
+            Paint::Hud => self.hud,
+            Paint::Alert => self.alert,
+            Paint::Notice => self.notice,
+            Paint::Bridge => self.bridge,
+            Paint::FuelDepot => self.fuel_depot,
+            Paint::EnemyBullet => self.enemy_bullet,
+            Paint::Unlocked => self.unlocked,
+            Paint::Locked => self.locked,
+            Paint::GaugeHigh => self.gauge_high,
+            Paint::GaugeMid => self.gauge_mid,
+            Paint::GaugeLow => self.gauge_low,
+            Paint::Ghost => self.ghost,
+        }
+    }
+}
+
+/// @notice how the game looks, the palette and the glyphs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub name: ThemeName,
+    pub palette: Palette,
+    pub glyphs: GlyphSet,
+    pub depth: ColorDepth,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::new(
+            ThemeName::default(),
+            GlyphSet::default(),
+            ColorDepth::default(),
+        )
+    }
+}
+
+impl Theme {
+    pub fn new(name: ThemeName, glyphs: GlyphSet, depth: ColorDepth) -> Self {
+        Theme {
+            name,
+            palette: Palette::of(name),
+            glyphs,
+            depth,
+        }
+    }
+
+    /// @notice the theme, glyphs and colours the terminal can show, from `TERM`, `COLORTERM`,
+    /// `NO_COLOR` and the locale.
+    /// @dev the ones chosen in the settings win over the detected ones.
+    pub fn detect(settings: &GameSettings) -> Self {
+        let var = |key: &str| std::env::var(key).unwrap_or_default();
+        let term = var("TERM");
+        let colorterm = var("COLORTERM");
+        let locale = [var("LC_ALL"), var("LC_CTYPE"), var("LANG")]
+            .into_iter()
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        // the bare consoles have neither colours worth the name nor the emoji fonts.
+        let bare_console = term == "dumb" || term.starts_with("vt");
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        let detected_name = if (bare_console || no_color) && colorterm.is_empty() {
+            ThemeName::Monochrome
+        } else {
+            ThemeName::Classic
+        };
+
+        let utf8 = locale.contains("utf-8") || locale.contains("utf8");
+        let detected_glyphs = if bare_console || term == "linux" || !utf8 {
+            GlyphSet::Ascii
+        } else {
+            GlyphSet::Emoji
+        };
+
+        let detected_depth = if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        };
+
+        Theme::new(
+            settings.theme.unwrap_or(detected_name),
+            settings.glyphs.unwrap_or(detected_glyphs),
+            settings.colors.unwrap_or(detected_depth),
+        )
+    }
+
+    pub fn paint(&self, paint: Paint) -> Colors {
+        self.palette.get(paint)
+    }
+
+    /// @notice the emoji or the ascii version of a glyph, whichever the theme uses.
+    pub fn glyph<'a>(&self, emoji: &'a str, ascii: &'a str) -> &'a str {
+        match self.glyphs {
+            GlyphSet::Emoji => emoji,
+            GlyphSet::Ascii => ascii,
+        }
+    }
+
+    // the gradients, when the terminal can draw them.
+    fn gradients(&self) -> Option<Gradients> {
+        self.palette
+            .gradients
+            .filter(|_| self.depth != ColorDepth::Ansi16)
+    }
+
+    /// @notice the water `from_bank` columns away from the closest bank, the river is deepest in its middle.
+    pub fn water(&self, from_bank: u16, half_width: u16) -> Colors {
+        let Some(gradients) = self.gradients() else {
+            return self.paint(Paint::Water);
+        };
+        let depth = from_bank as f32 / half_width.max(1) as f32;
+        let water = lerp(gradients.shallow_water, gradients.deep_water, depth);
+        Colors::gradient(None, self.depth.rgb(water))
+    }
+
+    /// @notice the bank `from_water` columns away from the river, the shore is shaded towards the inland.
+    pub fn bank(&self, from_water: u16) -> Colors {
+        let Some(gradients) = self.gradients() else {
+            return self.paint(Paint::Bank);
+        };
+        let bank = self.depth.rgb(lerp(
+            gradients.shore,
+            gradients.inland,
+            from_water as f32 / SHORE_WIDTH,
+        ));
+        Colors::gradient(bank, bank)
+    }
+
+    /// @notice the colour of an explosion `progress` (0 to 1) of the way through its animation.
+    pub fn explosion(&self, progress: f32) -> Colors {
+        if self.name == ThemeName::Monochrome {
+            return Colors::NONE;
+        }
+
+        let progress = progress.clamp(0.0, 1.0) * (EXPLOSION.len() - 1) as f32;
+        let stop = (progress as usize).min(EXPLOSION.len() - 2);
+        match self.depth.rgb(lerp(
+            EXPLOSION[stop],
+            EXPLOSION[stop + 1],
+            progress - stop as f32,
+        )) {
+            Some(color) => Colors::text(color),
+            None => Colors::text(EXPLOSION_16[progress.round() as usize]),
+        }
+    }
+
+    /// @notice the whole river flashes when the boat is hit.
+    pub fn damage_flash(&self) -> Colors {
+        if self.name == ThemeName::Monochrome {
+            return Colors::NONE;
+        }
+        let flash = self.depth.rgb(DAMAGE_FLASH).unwrap_or(Color::DarkRed);
+        Colors::gradient(Some(flash), Some(flash))
+    }
+
+    /// @notice what the banks are filled with, without a colour they need a visible character.
+    pub fn bank_fill(&self) -> char {
+        match (self.palette.bank.background, self.glyphs) {
+            (Some(_), _) => ' ',
+            (None, GlyphSet::Emoji) => '▒',
+            (None, GlyphSet::Ascii) => '#',
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::ecs::Components;
+use crate::enemy::SPREAD_SLOPE;
+use crate::events::GameEvent;
+use crate::powerup::PowerUpKind;
+use crate::{Game2DMatrix, Location, BULLET_SPEED};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// @notice what limits the gun besides its cooldown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum WeaponMode {
+    #[default]
+    Unlimited,
+    // every shot uses a round, the gun is empty at zero.
+    Ammo,
+    // every shot heats the gun, it locks when overheated until it cooled down.
+    Heat,
+}
+
+impl FromStr for WeaponMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "unlimited" => Ok(WeaponMode::Unlimited),
+            "ammo" => Ok(WeaponMode::Ammo),
+            "heat" => Ok(WeaponMode::Heat),
+            other => Err(format!(
+                "unknown weapon mode `{other}`, expected unlimited, ammo or heat"
+            )),
+        }
+    }
+}
+
+impl Game2DMatrix {
+    /// @notice shoots from a boat with its power-ups, unless its gun is still cooling down.
+    /// @dev returns whether a shot was fired.
+    pub fn fire(&mut self, p: usize) -> bool {
+        if self.players[p].fire_cooldown > 0 || !self.weapon_ready(p) {
+            return false;
+        }
+
+        let player = &mut self.players[p];
+        player.fire_cooldown = if player.power_ups.is_active(PowerUpKind::RapidFire) {
+            self.settings.rapid_fire_cooldown_ticks
+        } else {
+            self.settings.fire_cooldown_ticks
+        };
+
+        let slopes: &[f32] = if player.power_ups.is_active(PowerUpKind::SpreadShot) {
+            &[-SPREAD_SLOPE, 0.0, SPREAD_SLOPE]
+        } else {
+            &[0.0]
+        };
+        let piercing = player.power_ups.is_active(PowerUpKind::Piercing);
+
+        let muzzle = Location::new(player.j as f32, player.i as f32);
+        for slope in slopes {
+            let mut bullet = Components::player_bullet(muzzle, p);
+            bullet.velocity.dj = slope * BULLET_SPEED;
+            bullet.piercing = piercing;
+            self.world.spawn(bullet);
+        }
+
+        self.events.push(GameEvent::ShotFired {
+            bullets: slopes.len() as u32,
+        });
+
+        // a spread shot is still a single pull of the trigger.
+        let player = &mut self.players[p];
+        match self.settings.weapon_mode {
+            WeaponMode::Unlimited => {}
+            WeaponMode::Ammo => player.ammo -= 1,
+            WeaponMode::Heat => {
+                player.heat += self.settings.heat_per_shot;
+                if player.heat >= self.settings.max_heat {
+                    player.overheated = true;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// @notice whether the mode of the weapon allows a player to shoot, regardless of the cooldown.
+    pub fn weapon_ready(&self, p: usize) -> bool {
+        let player = &self.players[p];
+        match self.settings.weapon_mode {
+            WeaponMode::Unlimited => true,
+            WeaponMode::Ammo => player.ammo > 0,
+            WeaponMode::Heat => !player.overheated,
+        }
+    }
+
+    /// @notice lets the guns cool down for a tick.
+    /// @dev an overheated gun unlocks once it is back to half of the max heat.
+    pub fn cool_down_weapons(&mut self) {
+        for player in &mut self.players {
+            player.fire_cooldown = player.fire_cooldown.saturating_sub(1);
+
+            player.heat = (player.heat - self.settings.heat_cooling).max(0.0);
+            if player.overheated && player.heat <= self.settings.max_heat / 2.0 {
+                player.overheated = false;
+            }
+        }
+    }
+
+    /// @notice refills the gun of a player by the given rounds, up to the capacity, and cools it down completely.
+    pub fn resupply(&mut self, p: usize, rounds: u32) {
+        let player = &mut self.players[p];
+        player.ammo = (player.ammo + rounds).min(self.settings.ammo_capacity);
+        player.heat = 0.0;
+        player.overheated = false;
+    }
+}